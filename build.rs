@@ -0,0 +1,109 @@
+//! Generate the table of suuhai for shanten.
+//!
+//! For every 9 counts of one suit (base-5 key, 1m as the lowest digit), the most number of
+//! taatsu and toitsu is calculated for each number of mentsu (0\~4) and whether a jantou
+//! is taken from this suit. Same as `Decomposer`, toitsu of the same hai cannot be
+//! duplicated. Each entry is 10 slots of 3 bits, `7` for impossible.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+const KEY_NUMBER: usize = 1953125; // 5^9
+const MAX_MENTSU: usize = 4;
+const MAX_PARTIAL: i8 = 6;
+
+type Blocks = [[i8; MAX_MENTSU + 1]; 2];
+
+fn main() {
+    let mut power = [1usize; 10];
+    for i in 1..10 {
+        power[i] = power[i - 1] * 5;
+    }
+
+    let mut table: Vec<Blocks> = vec![[[-1; MAX_MENTSU + 1]; 2]; KEY_NUMBER];
+    table[0][0][0] = 0;
+
+    for key in 1..KEY_NUMBER {
+        let mut counts = [0usize; 11];
+        for (i, count) in counts.iter_mut().take(9).enumerate() {
+            *count = key / power[i] % 5;
+        }
+        let i = counts.iter().position(|count| *count > 0).unwrap();
+        let count = counts[i];
+
+        let mut blocks: Blocks = [[-1; MAX_MENTSU + 1]; 2];
+        // Decide every block using the lowest hai at once: koutsu, toitsu, juntsu,
+        // taatsu with next one and taatsu with next two. The rest is ukihai.
+        for koutsu in 0..=count / 3 {
+            for toitsu in 0..=1 {
+                let used = 3 * koutsu + 2 * toitsu;
+                if used > count {
+                    continue;
+                }
+                for juntsu in 0..=count - used {
+                    for ryanmen in 0..=count - used - juntsu {
+                        for kanchan in 0..=count - used - juntsu - ryanmen {
+                            if i + 1 >= 9 && juntsu + ryanmen > 0 {
+                                continue;
+                            }
+                            if i + 2 >= 9 && juntsu + kanchan > 0 {
+                                continue;
+                            }
+                            if counts[i + 1] < juntsu + ryanmen || counts[i + 2] < juntsu + kanchan
+                            {
+                                continue;
+                            }
+
+                            let mut child = key - count * power[i];
+                            if juntsu + ryanmen > 0 {
+                                child -= (juntsu + ryanmen) * power[i + 1];
+                            }
+                            if juntsu + kanchan > 0 {
+                                child -= (juntsu + kanchan) * power[i + 2];
+                            }
+
+                            let mentsu = koutsu + juntsu;
+                            let taatsu = (ryanmen + kanchan) as i8;
+                            for jantou in 0..2 {
+                                for child_mentsu in 0..=MAX_MENTSU {
+                                    let partial = table[child][jantou][child_mentsu];
+                                    if partial < 0 || child_mentsu + mentsu > MAX_MENTSU {
+                                        continue;
+                                    }
+                                    let slot = &mut blocks[jantou][child_mentsu + mentsu];
+                                    // Toitsu as partial block.
+                                    *slot = (*slot).max(
+                                        (partial + taatsu + toitsu as i8).min(MAX_PARTIAL),
+                                    );
+                                    // Toitsu as jantou.
+                                    if toitsu == 1 && jantou == 0 {
+                                        let slot = &mut blocks[1][child_mentsu + mentsu];
+                                        *slot = (*slot).max((partial + taatsu).min(MAX_PARTIAL));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        table[key] = blocks;
+    }
+
+    let mut bytes = Vec::with_capacity(KEY_NUMBER * 4);
+    for blocks in &table {
+        let mut entry = 0u32;
+        for (jantou, partial_vec) in blocks.iter().enumerate() {
+            for (mentsu, partial) in partial_vec.iter().enumerate() {
+                let value = if *partial < 0 { 7 } else { *partial as u32 };
+                entry |= value << (3 * (jantou * (MAX_MENTSU + 1) + mentsu));
+            }
+        }
+        bytes.extend_from_slice(&entry.to_le_bytes());
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("suuhai_table.bin"), bytes).unwrap();
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,341 @@
+use super::tehai::Hourakei;
+use super::{Error, Fuuro, Hai, HaiStyle, Mentsu, StyledDisplay, Tehai, Toitsu};
+
+/// How agari hai completed the tehai.
+///
+/// # Japanese
+/// * Machi: 待ち
+/// * Ryanmen: 両面
+/// * Kanchan: 嵌張
+/// * Penchan: 辺張
+/// * Shanpon: 双碰
+/// * Tanki: 単騎
+/// * Juusanmen: 十三面 (kokushimusou only)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Machi {
+    Ryanmen,
+    Kanchan,
+    Penchan,
+    Shanpon,
+    Tanki,
+    Juusanmen,
+}
+
+/// One way to read a complete tehai, with the shape of waiting.
+///
+/// Fu and some yaku like pinfu depend on the reading, so all readings should be considered
+/// and the one with most points taken.
+///
+/// # Member
+/// * hourakei: form of the tehai.
+/// * toitsu_vec: the jantou of mentsute and kokushimusou, or all seven toitsu of chiitoitsu.
+/// * mentsu_vec: mentsu formed by juntehai, including the one completed by agari hai.
+/// * fuuro: mentsu already formed before winning.
+/// * agari_hai: the hai completed the tehai.
+/// * machi: how agari hai completed the tehai.
+///
+/// # Japanese
+/// * jantou: 雀頭
+/// * agari: 和了
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AgariDecomposer {
+    pub hourakei: Hourakei,
+    pub toitsu_vec: Vec<Toitsu>,
+    pub mentsu_vec: Vec<Mentsu>,
+    pub fuuro: Vec<Fuuro>,
+    pub agari_hai: Hai,
+    pub machi: Machi,
+}
+
+impl AgariDecomposer {
+    /// Return if no fuuro made by calling others. Ankan keeps menzen.
+    ///
+    /// # Japanese
+    /// * menzen: 門前
+    pub fn menzen(&self) -> bool {
+        self.fuuro.iter().all(|fuuro| !fuuro.is_open())
+    }
+
+    /// Return all mentsu of the reading, fuuro included.
+    pub fn all_mentsu(&self) -> Vec<Mentsu> {
+        self.mentsu_vec
+            .iter()
+            .copied()
+            .chain(self.fuuro.iter().map(|fuuro| fuuro.mentsu))
+            .collect()
+    }
+}
+
+fn remove_once(container: &mut Vec<Hai>, item: &Hai) -> bool {
+    if let Some(index) = container.iter().position(|cur| cur == item) {
+        container.remove(index);
+        true
+    } else {
+        false
+    }
+}
+
+/// Split sorted hai into mentsu only, pushing every possible result into output.
+fn split_mentsu(juntehai: &[Hai], mentsu_vec: &mut Vec<Mentsu>, output: &mut Vec<Vec<Mentsu>>) {
+    let first = match juntehai.first() {
+        Some(first) => *first,
+        None => {
+            output.push(mentsu_vec.clone());
+            return;
+        }
+    };
+
+    if juntehai.len() >= 3 && juntehai[1] == first && juntehai[2] == first {
+        mentsu_vec.push(Mentsu::Koutsu(first));
+        split_mentsu(&juntehai[3..], mentsu_vec, output);
+        mentsu_vec.pop();
+    }
+
+    if first.is_jihai() {
+        return;
+    }
+    if let Some(second) = first.next(4, false) {
+        if let Some(third) = second.next(4, false) {
+            let mut rest = juntehai[1..].to_vec();
+            if remove_once(&mut rest, &second) && remove_once(&mut rest, &third) {
+                mentsu_vec.push(Mentsu::Juntsu(first, second, third));
+                split_mentsu(&rest, mentsu_vec, output);
+                mentsu_vec.pop();
+            }
+        }
+    }
+}
+
+impl Tehai {
+    /// Decompose a complete tehai to all possible readings when winning with agari hai.
+    ///
+    /// Juntehai must include agari hai. Every block containing agari hai makes a different
+    /// reading, so `23444m` winning with `4m` is read both as ryanmen on `234m` and as tanki
+    /// on `44m`. Red five is read as normal five. An empty vec will be returned if the tehai
+    /// is not complete.
+    pub fn agari_decompose(&self, agari_hai: Hai) -> Result<Vec<AgariDecomposer>, Error> {
+        let tehai = self.normalize();
+        let agari_hai = agari_hai.normalize();
+        if tehai.juntehai.len() % 3 != 2 {
+            return Err(Error::WrongHaiNumber {
+                remainder: 2,
+                found: tehai.juntehai.len(),
+            });
+        }
+        if !tehai.juntehai.contains(&agari_hai) {
+            return Err(Error::AgariHaiNotFound(agari_hai));
+        }
+
+        let mut output = vec![];
+        let mut push_into_output = |decomposer: AgariDecomposer| {
+            if !output.contains(&decomposer) {
+                output.push(decomposer);
+            }
+        };
+
+        // Analyze Mentsute
+        let mut jantou_candidates = tehai.juntehai.clone();
+        jantou_candidates.dedup();
+        for jantou in jantou_candidates {
+            let mut rest = tehai.juntehai.clone();
+            if !(remove_once(&mut rest, &jantou) && remove_once(&mut rest, &jantou)) {
+                continue;
+            }
+
+            let mut mentsu_vec_vec = vec![];
+            split_mentsu(&rest, &mut vec![], &mut mentsu_vec_vec);
+            for mentsu_vec in mentsu_vec_vec {
+                let new_decomposer = |machi| AgariDecomposer {
+                    hourakei: Hourakei::Mentsute,
+                    toitsu_vec: vec![Toitsu(jantou)],
+                    mentsu_vec: mentsu_vec.clone(),
+                    fuuro: tehai.fuuro.clone(),
+                    agari_hai,
+                    machi,
+                };
+
+                if jantou == agari_hai {
+                    push_into_output(new_decomposer(Machi::Tanki));
+                }
+                for mentsu in &mentsu_vec {
+                    match *mentsu {
+                        Mentsu::Koutsu(hai) if hai == agari_hai => {
+                            push_into_output(new_decomposer(Machi::Shanpon));
+                        }
+                        Mentsu::Juntsu(a, b, c) => {
+                            if b == agari_hai {
+                                push_into_output(new_decomposer(Machi::Kanchan));
+                            } else if (a == agari_hai && c.number() == 9)
+                                || (c == agari_hai && a.number() == 1)
+                            {
+                                push_into_output(new_decomposer(Machi::Penchan));
+                            } else if a == agari_hai || c == agari_hai {
+                                push_into_output(new_decomposer(Machi::Ryanmen));
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        // Analyze Chiitoitsu and Kokushimusou.
+        if tehai.juntehai.len() != 14 || !tehai.fuuro.is_empty() {
+            return Ok(output);
+        }
+
+        // Analyze Chiitoitsu
+        let toitsu_vec: Vec<Toitsu> = tehai
+            .juntehai
+            .chunks(2)
+            .filter(|pair| pair[0] == pair[1])
+            .map(|pair| Toitsu(pair[0]))
+            .collect();
+        let mut distinct_toitsu = toitsu_vec.clone();
+        distinct_toitsu.dedup();
+        if distinct_toitsu.len() == 7 {
+            push_into_output(AgariDecomposer {
+                hourakei: Hourakei::Chiitoitsu,
+                toitsu_vec,
+                mentsu_vec: vec![],
+                fuuro: vec![],
+                agari_hai,
+                machi: Machi::Tanki,
+            });
+        }
+
+        // Analyze Kokushimusou
+        let mut distinct_hai = tehai.juntehai.clone();
+        distinct_hai.dedup();
+        if tehai.juntehai.iter().all(|hai| hai.is_yaochuupai()) && distinct_hai.len() == 13 {
+            let jantou = tehai
+                .juntehai
+                .windows(2)
+                .find(|pair| pair[0] == pair[1])
+                .map(|pair| pair[0])
+                .ok_or(Error::Logic("Kokushimusou without jantou."))?;
+            push_into_output(AgariDecomposer {
+                hourakei: Hourakei::Kokushimusou,
+                toitsu_vec: vec![Toitsu(jantou)],
+                mentsu_vec: vec![],
+                fuuro: vec![],
+                agari_hai,
+                machi: if jantou == agari_hai {
+                    Machi::Juusanmen
+                } else {
+                    Machi::Tanki
+                },
+            });
+        }
+
+        Ok(output)
+    }
+}
+
+impl std::fmt::Display for Machi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Machi::Ryanmen => "両面",
+                Machi::Kanchan => "嵌張",
+                Machi::Penchan => "辺張",
+                Machi::Shanpon => "双碰",
+                Machi::Tanki => "単騎",
+                Machi::Juusanmen => "十三面",
+            }
+        )
+    }
+}
+
+/// Print like `1z1z 1m2m3m 2z2z2z [4p5p6p] 嵌張`, fuuro in the same way as input.
+impl StyledDisplay for AgariDecomposer {
+    fn fmt_styled(&self, f: &mut std::fmt::Formatter<'_>, style: HaiStyle) -> std::fmt::Result {
+        for (index, Toitsu(hai)) in self.toitsu_vec.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}{}", hai.styled(style), hai.styled(style))?;
+        }
+        for mentsu in &self.mentsu_vec {
+            write!(f, " ")?;
+            for hai in mentsu.hai_vec() {
+                write!(f, "{}", hai.styled(style))?;
+            }
+        }
+        if !self.fuuro.is_empty() {
+            write!(f, " ")?;
+        }
+        for fuuro in &self.fuuro {
+            write!(f, "{}", fuuro.styled(style))?;
+        }
+        write!(f, " {}", self.machi)
+    }
+}
+
+impl std::fmt::Display for AgariDecomposer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_styled(f, HaiStyle::Text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decompose(tehai: &str, agari_hai: Hai) -> Vec<AgariDecomposer> {
+        Tehai::new(tehai.to_string())
+            .unwrap()
+            .agari_decompose(agari_hai)
+            .unwrap()
+    }
+
+    /// Return machi of every reading, in order.
+    fn machi(tehai: &str, agari_hai: Hai) -> Vec<Machi> {
+        decompose(tehai, agari_hai)
+            .iter()
+            .map(|decomposer| decomposer.machi)
+            .collect()
+    }
+
+    #[test]
+    fn machi_kind() {
+        let head = "123p456p789s";
+        let check = |tail: &str, agari_hai: Hai, expected: &[Machi]| {
+            let mut found = machi(&format!("{}{}", head, tail), agari_hai);
+            found.sort_by_key(|machi| *machi as u8);
+            assert_eq!(found, expected, "{} {}", tail, agari_hai);
+        };
+        check("23444m", Hai::Manzu(4), &[Machi::Ryanmen, Machi::Tanki]);
+        check("23444m", Hai::Manzu(2), &[Machi::Ryanmen]);
+        check("24344m", Hai::Manzu(3), &[Machi::Kanchan]);
+        check("12344m", Hai::Manzu(3), &[Machi::Penchan]);
+        check("78944m", Hai::Manzu(7), &[Machi::Penchan]);
+        check("44m11z1z", Hai::Jihai(1), &[Machi::Shanpon]);
+        check("11234m", Hai::Manzu(1), &[Machi::Tanki]);
+        check("11123m", Hai::Manzu(1), &[Machi::Ryanmen, Machi::Tanki]);
+        check("45666m", Hai::Manzu(6), &[Machi::Ryanmen, Machi::Tanki]);
+        assert!(machi("123p456p789s23445m", Hai::Manzu(5)).is_empty());
+
+        let decomposers = decompose("123p456p789s44m[123m]", Hai::Manzu(4));
+        assert_eq!(decomposers.len(), 1);
+        assert_eq!(decomposers[0].machi, Machi::Tanki);
+        assert_eq!(decomposers[0].mentsu_vec.len(), 3);
+        assert_eq!(decomposers[0].fuuro.len(), 1);
+    }
+
+    #[test]
+    fn aka_chiitoitsu_and_kokushimusou() {
+        let decomposers = decompose("1199m1199p11s05m11z", Hai::Manzu(0));
+        assert_eq!(decomposers.len(), 1);
+        assert_eq!(decomposers[0].hourakei, Hourakei::Chiitoitsu);
+        assert!(decomposers[0].toitsu_vec.contains(&Toitsu(Hai::Manzu(5))));
+
+        let decomposers = decompose("19m19p19s1234567z1z", Hai::Jihai(1));
+        assert_eq!(decomposers.len(), 1);
+        assert_eq!(decomposers[0].hourakei, Hourakei::Kokushimusou);
+        assert_eq!(decomposers[0].toitsu_vec, [Toitsu(Hai::Jihai(1))]);
+        assert_eq!(decomposers[0].machi, Machi::Juusanmen);
+    }
+}
@@ -37,83 +37,69 @@ pub struct Ukihai(pub Hai);
 
 impl Mentsu {
     /// Create a mentsu from input vec of hai if they can make up a valid mentsu.
-    pub fn new(hai_vec: &Vec<Hai>) -> Option<Self> {
-        fn check_juntsu(mut a: u8, mut b: u8, mut c: u8) -> Option<(u8, u8, u8)> {
-            if a > b {
-                std::mem::swap(&mut a, &mut b)
-            }
-            if a > c {
-                std::mem::swap(&mut a, &mut c)
-            }
-            if b > c {
-                std::mem::swap(&mut b, &mut c)
-            }
-            if a + 1 == b && b + 1 == c {
-                Some((a, b, c))
-            } else {
-                None
-            }
+    ///
+    /// Red five is kept, and for koutsu and kantsu the red one represents the mentsu,
+    /// like `Koutsu(Manzu(0))` for `550m`. Hai not used in 3-players mode is not checked here.
+    pub fn new(hai_vec: &[Hai]) -> Option<Self> {
+        if !Hai::check_iter_valid(hai_vec.iter(), 4) {
+            return None;
         }
 
-        if !Hai::check_iter_valid(hai_vec.iter()) {
-            None
-        } else if hai_vec.len() == 4 {
-            if hai_vec[0] == hai_vec[1] && hai_vec[0] == hai_vec[2] && hai_vec[0] == hai_vec[3] {
-                Some(Mentsu::Kantsu(hai_vec[0]))
-            } else {
-                None
-            }
-        } else if hai_vec.len() == 3 {
-            if hai_vec[0] == hai_vec[1] && hai_vec[0] == hai_vec[2] {
-                Some(Mentsu::Koutsu(hai_vec[0]))
-            } else {
-                match (hai_vec[0], hai_vec[1], hai_vec[2]) {
-                    (Hai::Manzu(a), Hai::Manzu(b), Hai::Manzu(c)) => {
-                        let (a, b, c) = check_juntsu(a, b, c)?;
-                        Some(Mentsu::Juntsu(Hai::Manzu(a), Hai::Manzu(b), Hai::Manzu(c)))
-                    },
-                    (Hai::Pinzu(a), Hai::Pinzu(b), Hai::Pinzu(c)) => {
-                        let (a, b, c) = check_juntsu(a, b, c)?;
-                        Some(Mentsu::Juntsu(Hai::Pinzu(a), Hai::Pinzu(b), Hai::Pinzu(c)))
-                    }
-                    (Hai::Souzu(a), Hai::Souzu(b), Hai::Souzu(c)) => {
-                        let (a, b, c) = check_juntsu(a, b, c)?;
-                        Some(Mentsu::Juntsu(Hai::Souzu(a), Hai::Souzu(b), Hai::Souzu(c)))
-                    }
-                    _ => None,
-                }
+        let mut sorted = hai_vec.to_vec();
+        sorted.sort();
+        let first = sorted.first()?.normalize();
+
+        if sorted.iter().all(|hai| hai.normalize() == first) {
+            let hai = sorted.iter().find(|hai| hai.is_aka()).copied().unwrap_or(first);
+            match sorted.len() {
+                3 => Some(Mentsu::Koutsu(hai)),
+                4 => Some(Mentsu::Kantsu(hai)),
+                _ => None,
             }
+        } else if sorted.len() == 3
+            && !first.is_jihai()
+            && Some(sorted[1].normalize()) == first.next(4, false)
+            && Some(sorted[2].normalize()) == sorted[1].next(4, false)
+        {
+            Some(Mentsu::Juntsu(sorted[0], sorted[1], sorted[2]))
         } else {
             None
         }
     }
+
+    /// Return all hai in mentsu, like `[3m, 4m, 5m]` for `Juntsu(3m, 4m, 5m)`
+    /// and `[5m, 5m, 0m]` for `Koutsu(0m)`.
+    pub fn hai_vec(&self) -> Vec<Hai> {
+        match *self {
+            Mentsu::Juntsu(a, b, c) => vec![a, b, c],
+            Mentsu::Koutsu(a) => vec![a.normalize(), a.normalize(), a],
+            Mentsu::Kantsu(a) => vec![a.normalize(), a.normalize(), a.normalize(), a],
+        }
+    }
+
+    /// Return the mentsu with red five replaced by normal five.
+    pub fn normalize(&self) -> Mentsu {
+        match *self {
+            Mentsu::Juntsu(a, b, c) => Mentsu::Juntsu(a.normalize(), b.normalize(), c.normalize()),
+            Mentsu::Koutsu(a) => Mentsu::Koutsu(a.normalize()),
+            Mentsu::Kantsu(a) => Mentsu::Kantsu(a.normalize()),
+        }
+    }
 }
 
 impl std::fmt::Display for Mentsu {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Mentsu::Juntsu(a, b, c) => {
-                    format!("[{}{}{}]", a.to_string(), b.to_string(), c.to_string())
-                }
-                Mentsu::Koutsu(a) => {
-                    let tile = a.to_string();
-                    format!("[{}{}{}]", tile, tile, tile)
-                }
-                Mentsu::Kantsu(a) => {
-                    let tile = a.to_string();
-                    format!("[{}{}{}{}]", tile, tile, tile, tile)
-                }
-            }
-        )
+        write!(f, "[")?;
+        for hai in self.hai_vec() {
+            write!(f, "{}", hai)?;
+        }
+        write!(f, "]")
     }
 }
 
 impl std::fmt::Display for Taatsu {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}", self.0.to_string(), self.1.to_string())
+        write!(f, "{}{}", self.0, self.1)
     }
 }
 
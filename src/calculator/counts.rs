@@ -0,0 +1,269 @@
+use super::{Hai, Tehai};
+
+/// Number of index, 34 types of hai and 3 red five.
+pub const HAI_INDEX_NUMBER: usize = 37;
+
+/// Number of each hai, with O(1) add and remove.
+///
+/// Red five is counted apart from normal five. Use `normalized` or `count_normalized`
+/// when red five should work as normal five.
+///
+/// It counts hai left to draw in `Tehai::analyze`, `ukeire` and `kairyou`, and feeds the
+/// shanten table. Decomposing tehai in `analyze` still works on sorted juntehai.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HaiCounts([u8; HAI_INDEX_NUMBER]);
+
+impl Hai {
+    /// Return the stable index of hai: 1\~9m as 0\~8, 1\~9p as 9\~17, 1\~9s as 18\~26,
+    /// 1\~7z as 27\~33, and 0m, 0p, 0s as 34, 35, 36.
+    pub fn index(&self) -> usize {
+        match *self {
+            Hai::Manzu(0) => 34,
+            Hai::Pinzu(0) => 35,
+            Hai::Souzu(0) => 36,
+            Hai::Manzu(num) => num as usize - 1,
+            Hai::Pinzu(num) => num as usize + 8,
+            Hai::Souzu(num) => num as usize + 17,
+            Hai::Jihai(num) => num as usize + 26,
+        }
+    }
+
+    /// Return hai of the index, reverse of `index`.
+    pub fn from_index(index: usize) -> Option<Hai> {
+        match index {
+            0..=8 => Some(Hai::Manzu(index as u8 + 1)),
+            9..=17 => Some(Hai::Pinzu(index as u8 - 8)),
+            18..=26 => Some(Hai::Souzu(index as u8 - 17)),
+            27..=33 => Some(Hai::Jihai(index as u8 - 26)),
+            34 => Some(Hai::Manzu(0)),
+            35 => Some(Hai::Pinzu(0)),
+            36 => Some(Hai::Souzu(0)),
+            _ => None,
+        }
+    }
+}
+
+impl HaiCounts {
+    /// Create empty counts.
+    pub fn new() -> Self {
+        Self([0; HAI_INDEX_NUMBER])
+    }
+
+    /// Add a hai.
+    pub fn add(&mut self, hai: &Hai) {
+        self.0[hai.index()] += 1;
+    }
+
+    /// Remove a hai. Return false if no such hai.
+    pub fn remove(&mut self, hai: &Hai) -> bool {
+        let count = &mut self.0[hai.index()];
+        if *count == 0 {
+            false
+        } else {
+            *count -= 1;
+            true
+        }
+    }
+
+    /// Return the number of hai, red five and normal five counted apart.
+    pub fn count(&self, hai: &Hai) -> u8 {
+        self.0[hai.index()]
+    }
+
+    /// Return the number of hai, red five counted as normal five.
+    pub fn count_normalized(&self, hai: &Hai) -> u8 {
+        let normal = hai.normalize();
+        let aka = match normal {
+            Hai::Manzu(5) => self.0[34],
+            Hai::Pinzu(5) => self.0[35],
+            Hai::Souzu(5) => self.0[36],
+            _ => 0,
+        };
+        self.0[normal.index()] + aka
+    }
+
+    /// Return the number of all hai.
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|count| *count as usize).sum()
+    }
+
+    /// Return true if no hai.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|count| *count == 0)
+    }
+
+    /// Return 34 counts with red five counted as normal five, indexed by `Hai::index`.
+    pub fn normalized(&self) -> [u8; 34] {
+        let mut counts = [0; 34];
+        counts.copy_from_slice(&self.0[..34]);
+        counts[4] += self.0[34];
+        counts[13] += self.0[35];
+        counts[22] += self.0[36];
+        counts
+    }
+
+    /// Return normalized counts of 1\~9m.
+    pub fn manzu(&self) -> [u8; 9] {
+        let mut counts = [0; 9];
+        counts.copy_from_slice(&self.normalized()[0..9]);
+        counts
+    }
+
+    /// Return normalized counts of 1\~9p.
+    pub fn pinzu(&self) -> [u8; 9] {
+        let mut counts = [0; 9];
+        counts.copy_from_slice(&self.normalized()[9..18]);
+        counts
+    }
+
+    /// Return normalized counts of 1\~9s.
+    pub fn souzu(&self) -> [u8; 9] {
+        let mut counts = [0; 9];
+        counts.copy_from_slice(&self.normalized()[18..27]);
+        counts
+    }
+
+    /// Return counts of 1\~7z.
+    pub fn jihai(&self) -> [u8; 7] {
+        let mut counts = [0; 7];
+        counts.copy_from_slice(&self.0[27..34]);
+        counts
+    }
+
+    /// Iterate every hai found with its number, in the order of index.
+    pub fn iter(&self) -> impl Iterator<Item = (Hai, u8)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .filter_map(|(index, count)| Some((Hai::from_index(index)?, *count)))
+    }
+
+    /// Return all hai sorted, same as juntehai.
+    pub fn to_vec(&self) -> Vec<Hai> {
+        let mut hai_vec: Vec<Hai> = self
+            .iter()
+            .flat_map(|(hai, count)| std::iter::repeat_n(hai, count as usize))
+            .collect();
+        hai_vec.sort();
+        hai_vec
+    }
+}
+
+impl Default for HaiCounts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&[Hai]> for HaiCounts {
+    fn from(hai_vec: &[Hai]) -> Self {
+        let mut counts = Self::new();
+        for hai in hai_vec {
+            counts.add(hai);
+        }
+        counts
+    }
+}
+
+impl From<&HaiCounts> for Vec<Hai> {
+    fn from(counts: &HaiCounts) -> Self {
+        counts.to_vec()
+    }
+}
+
+/// Counts of juntehai, fuuro not included.
+impl From<&Tehai> for HaiCounts {
+    fn from(tehai: &Tehai) -> Self {
+        Self::from(&tehai.juntehai[..])
+    }
+}
+
+impl Tehai {
+    /// Return counts of juntehai.
+    pub fn counts(&self) -> HaiCounts {
+        HaiCounts::from(self)
+    }
+
+    /// Create menzen tehai from counts of juntehai.
+    pub fn from_counts(counts: &HaiCounts, player_number: u8) -> Self {
+        Self {
+            juntehai: counts.to_vec(),
+            fuuro: vec![],
+            nukidora: 0,
+            player_number,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index() {
+        for index in 0..HAI_INDEX_NUMBER {
+            let hai = Hai::from_index(index).unwrap();
+            assert_eq!(hai.index(), index, "{}", hai);
+        }
+        assert_eq!(Hai::from_index(HAI_INDEX_NUMBER), None);
+        assert_eq!(Hai::Manzu(1).index(), 0);
+        assert_eq!(Hai::Pinzu(5).index(), 13);
+        assert_eq!(Hai::Souzu(9).index(), 26);
+        assert_eq!(Hai::Jihai(7).index(), 33);
+        assert_eq!(Hai::Souzu(0).index(), 36);
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let mut counts = HaiCounts::new();
+        assert!(counts.is_empty());
+        counts.add(&Hai::Manzu(5));
+        counts.add(&Hai::Manzu(0));
+        counts.add(&Hai::Jihai(1));
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts.count(&Hai::Manzu(5)), 1);
+        assert_eq!(counts.count_normalized(&Hai::Manzu(5)), 2);
+        assert_eq!(counts.count_normalized(&Hai::Manzu(0)), 2);
+        assert_eq!(counts.count_normalized(&Hai::Pinzu(5)), 0);
+
+        assert!(counts.remove(&Hai::Manzu(0)));
+        assert!(!counts.remove(&Hai::Manzu(0)));
+        assert_eq!(counts.count_normalized(&Hai::Manzu(5)), 1);
+        assert_eq!(counts.len(), 2);
+    }
+
+    #[test]
+    fn normalized() {
+        let tehai = Tehai::new("1230m0555p9s77z".to_string()).unwrap();
+        let counts = tehai.counts();
+        let normalized = counts.normalized();
+        assert_eq!(normalized[Hai::Manzu(5).index()], 1);
+        assert_eq!(normalized[Hai::Pinzu(5).index()], 4);
+        assert_eq!(normalized[Hai::Jihai(7).index()], 2);
+        assert_eq!(normalized.iter().sum::<u8>(), 11);
+        assert_eq!(counts.manzu(), [1, 1, 1, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(counts.pinzu(), [0, 0, 0, 0, 4, 0, 0, 0, 0]);
+        assert_eq!(counts.souzu(), [0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(counts.jihai(), [0, 0, 0, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    fn conversion() {
+        let tehai = Tehai::new("1230m0555p9s77z[111z]".to_string()).unwrap();
+        let counts = tehai.counts();
+        assert_eq!(counts.len(), tehai.juntehai.len());
+        assert_eq!(counts.to_vec(), tehai.juntehai);
+        assert_eq!(Vec::<Hai>::from(&counts), tehai.juntehai);
+        assert_eq!(HaiCounts::from(&tehai.juntehai[..]), counts);
+        assert_eq!(
+            counts.iter().collect::<Vec<_>>()[..3],
+            [(Hai::Manzu(1), 1), (Hai::Manzu(2), 1), (Hai::Manzu(3), 1)]
+        );
+
+        let menzen = Tehai::from_counts(&counts, 4);
+        assert_eq!(menzen.juntehai, tehai.juntehai);
+        assert!(menzen.fuuro.is_empty());
+        assert_eq!(menzen.counts(), counts);
+    }
+}
@@ -0,0 +1,218 @@
+use super::Hai;
+
+/// Error of parsing and analyzing tehai.
+///
+/// All index are character index of input string, starting from 0.
+///
+/// # Variant
+/// * UnusedTypeCharacter: type character without number before it, like `m` in `m123`.
+/// * MissingTypeCharacter: number without type character. `chr` is the character found
+///   instead with its index, or `None` at the end of input with index of the first number.
+/// * UnusedAkaCharacter: `r` of mjai style red five not right after a single five, like
+///   `r` in `55mr` or `4mr`.
+/// * UnknownCharacter: character not used by hai, brackets, marks or space.
+/// * InvalidHai: hai not existing, like `8z`, or `2m` on 3-players mode. Index of the number.
+/// * InvalidMentsu: hai in `[]` or `()` cannot make up a fuuro. Index of `]` or `)`.
+/// * NestedBracket: `[` or `(` found in `[]` or `()`.
+/// * UnmatchedBracket: `]` or `)` found without `[` or `(` of the same kind.
+/// * UnclosedBracket: `[` or `(` not closed till the end of input.
+/// * UnexpectedBracket: `[]` or `()` found where only hai allowed, like dora indicators.
+/// * TooManyHai: more than 4 same hai, or more than 1 red five of a suit. Index of the hai
+///   over limit in input string, or `None` if found with hai on taku.
+/// * InvalidTakuHai: hai on taku not existing in current mode.
+/// * WrongHaiNumber: juntehai must be `3*k+remainder` but `found` provided.
+/// * AgariHaiNotFound: agari hai is not in juntehai.
+/// * HaiNotFound: hai to be discarded or called with is not in juntehai.
+/// * InvalidKaze: hai is not a wind, which must be 1\~4z.
+/// * NotAgari: tehai is not complete when calculating points.
+/// * Logic: a bug of this crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    UnusedTypeCharacter { chr: char, index: usize },
+    MissingTypeCharacter { chr: Option<char>, index: usize },
+    UnusedAkaCharacter { index: usize },
+    UnknownCharacter { chr: char, index: usize },
+    InvalidHai { hai: Hai, index: usize },
+    InvalidMentsu { index: usize },
+    NestedBracket { index: usize },
+    UnmatchedBracket { index: usize },
+    UnclosedBracket { index: usize },
+    UnexpectedBracket { index: usize },
+    TooManyHai { hai: Hai, number: u8, index: Option<usize> },
+    InvalidTakuHai(Hai),
+    WrongHaiNumber { remainder: usize, found: usize },
+    AgariHaiNotFound(Hai),
+    HaiNotFound(Hai),
+    InvalidKaze(Hai),
+    NotAgari,
+    Logic(&'static str),
+}
+
+impl Error {
+    /// Return the character index in input string if any.
+    pub fn index(&self) -> Option<usize> {
+        match *self {
+            Error::UnusedTypeCharacter { index, .. }
+            | Error::MissingTypeCharacter { index, .. }
+            | Error::UnusedAkaCharacter { index }
+            | Error::UnknownCharacter { index, .. }
+            | Error::InvalidHai { index, .. }
+            | Error::InvalidMentsu { index }
+            | Error::NestedBracket { index }
+            | Error::UnmatchedBracket { index }
+            | Error::UnclosedBracket { index }
+            | Error::UnexpectedBracket { index } => Some(index),
+            Error::TooManyHai { index, .. } => index,
+            _ => None,
+        }
+    }
+
+    /// Return the hai causing error if any.
+    pub fn hai(&self) -> Option<Hai> {
+        match *self {
+            Error::InvalidHai { hai, .. }
+            | Error::TooManyHai { hai, .. }
+            | Error::InvalidTakuHai(hai)
+            | Error::AgariHaiNotFound(hai)
+            | Error::HaiNotFound(hai)
+            | Error::InvalidKaze(hai) => Some(hai),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UnusedTypeCharacter { chr, index } => {
+                write!(f, "Unused type character '{}' at index {}.", chr, index)
+            }
+            Error::MissingTypeCharacter {
+                chr: Some(chr),
+                index,
+            } => write!(f, "Need 'm' 'p' 's' 'z' but find '{}' at index {}.", chr, index),
+            Error::MissingTypeCharacter { chr: None, index } => write!(
+                f,
+                "No type specified for number at index {} till the end of input string.",
+                index
+            ),
+            Error::UnusedAkaCharacter { index } => write!(
+                f,
+                "Unused aka character 'r' at index {}, which must follow a single five like 5mr.",
+                index
+            ),
+            Error::UnknownCharacter { chr, index } => {
+                write!(f, "Unknown character '{}' at index {}.", chr, index)
+            }
+            Error::InvalidHai { hai, index } => {
+                write!(f, "'{}' is invalid hai at index {}.", hai, index)
+            }
+            Error::InvalidMentsu { index } => {
+                write!(f, "Not a valid meld before index {}.", index)
+            }
+            Error::NestedBracket { index } => {
+                write!(f, "Second bracket found at index {}.", index)
+            }
+            Error::UnmatchedBracket { index } => {
+                write!(f, "Unmatched bracket found at index {}.", index)
+            }
+            Error::UnclosedBracket { index } => {
+                write!(f, "Unclosed bracket found at index {}.", index)
+            }
+            Error::UnexpectedBracket { index } => {
+                write!(f, "Fuuro is not allowed here, found at index {}.", index)
+            }
+            Error::TooManyHai { hai, number, index } => {
+                let ordinal = if *number == 2 { "Second" } else { "Fifth" };
+                match index {
+                    Some(index) => write!(f, "{} {} found at index {}.", ordinal, hai, index),
+                    None => write!(f, "{} {} found.", ordinal, hai),
+                }
+            }
+            Error::InvalidTakuHai(hai) => write!(f, "'{}' is invalid hai on taku.", hai),
+            Error::WrongHaiNumber { remainder, found } => write!(
+                f,
+                "The number of hai on hand must be 3*k+{}, such as {}, {}, {}, even {}, \
+                but {} provided.",
+                remainder,
+                3 * 2 + remainder,
+                3 * 3 + remainder,
+                3 * 4 + remainder,
+                3 * 5 + remainder,
+                found
+            ),
+            Error::AgariHaiNotFound(hai) => write!(f, "Agari hai {} is not in juntehai.", hai),
+            Error::HaiNotFound(hai) => write!(f, "{} is not in juntehai.", hai),
+            Error::InvalidKaze(hai) => write!(f, "'{}' is not a wind.", hai),
+            Error::NotAgari => write!(f, "Not a complete tehai."),
+            Error::Logic(message) => write!(f, "Logic Error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::{Taku, Tehai};
+
+    fn parse(input: &str) -> Error {
+        Tehai::new(input.to_string()).unwrap_err()
+    }
+
+    #[test]
+    fn index_of_parse_error() {
+        assert_eq!(
+            parse("m123m"),
+            Error::UnusedTypeCharacter { chr: 'm', index: 0 }
+        );
+        assert_eq!(
+            parse("123m45[111z]"),
+            Error::MissingTypeCharacter {
+                chr: Some('['),
+                index: 6
+            }
+        );
+        assert_eq!(
+            parse("123m45x"),
+            Error::UnknownCharacter { chr: 'x', index: 6 }
+        );
+        assert_eq!(
+            parse("123m 45"),
+            Error::MissingTypeCharacter {
+                chr: None,
+                index: 5
+            }
+        );
+        assert_eq!(parse("12m8z").index(), Some(3));
+        assert_eq!(parse("12m8z").hai(), Some(Hai::Jihai(8)));
+        assert_eq!(parse("11z[124m]"), Error::InvalidMentsu { index: 8 });
+        assert_eq!(parse("11z[12[3m]"), Error::NestedBracket { index: 6 });
+        assert_eq!(parse("11z]"), Error::UnmatchedBracket { index: 3 });
+        assert_eq!(parse("11z[123m"), Error::UnclosedBracket { index: 3 });
+        assert_eq!(
+            Taku::parse_hai_vec("1z[3p]", 4),
+            Err(Error::UnexpectedBracket { index: 2 })
+        );
+    }
+
+    #[test]
+    fn no_index() {
+        let tehai = Tehai::new("112m".to_string()).unwrap();
+        let error = tehai.ukeire(&Taku::default()).unwrap_err();
+        assert_eq!(error.index(), None);
+        assert_eq!(
+            error,
+            Error::WrongHaiNumber {
+                remainder: 1,
+                found: 3
+            }
+        );
+        assert_eq!(Error::NotAgari.index(), None);
+        assert_eq!(
+            Error::AgariHaiNotFound(Hai::Souzu(3)).hai(),
+            Some(Hai::Souzu(3))
+        );
+    }
+}
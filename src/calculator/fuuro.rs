@@ -0,0 +1,175 @@
+use super::{Hai, HaiStyle, Mentsu, StyledDisplay};
+
+/// How a fuuro was made.
+///
+/// # Japanese
+/// * Chii: 吃
+/// * Pon: 碰
+/// * Daiminkan: 大明槓
+/// * Kakan: 加槓
+/// * Ankan: 暗槓
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FuuroKind {
+    Chii,
+    Pon,
+    Daiminkan,
+    Kakan,
+    Ankan,
+}
+
+/// Other player relative to self.
+///
+/// # Japanese
+/// * Tacha: 他家
+/// * Shimocha: 下家, the next player, on the right.
+/// * Toimen: 対面, the player across.
+/// * Kamicha: 上家, the previous player, on the left.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Tacha {
+    Shimocha,
+    Toimen,
+    Kamicha,
+}
+
+/// A mentsu set aside from juntehai, by calling sutehai of others or by ankan.
+///
+/// # Input
+/// Written in `[]`, with `<`, `^` or `>` before the called hai for kamicha, toimen and
+/// shimocha. Kakan is a pon with `+` before the added hai. Ankan is written in `()`.
+/// * chii: `[<312m]`
+/// * pon: `[55^5p]`
+/// * daiminkan: `[>7777s]`
+/// * kakan: `[^555+5p]`
+/// * ankan: `(1111z)`
+///
+/// Without any mark, like `[123m]`, the called hai and the player are unknown, and kind is
+/// chii, pon or daiminkan by the mentsu.
+///
+/// # Member
+/// * kind: how the fuuro was made.
+/// * mentsu: the mentsu.
+/// * called: the hai called from others, or the one called by pon for kakan. `None` for
+///   ankan or unknown.
+/// * from: who `called` came from. `None` for ankan or unknown.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fuuro {
+    pub kind: FuuroKind,
+    pub mentsu: Mentsu,
+    pub called: Option<Hai>,
+    pub from: Option<Tacha>,
+}
+
+impl Fuuro {
+    /// Create a fuuro without knowing who it came from, kind decided by the mentsu.
+    pub fn new(mentsu: Mentsu) -> Self {
+        Self {
+            kind: match mentsu {
+                Mentsu::Juntsu(..) => FuuroKind::Chii,
+                Mentsu::Koutsu(_) => FuuroKind::Pon,
+                Mentsu::Kantsu(_) => FuuroKind::Daiminkan,
+            },
+            mentsu,
+            called: None,
+            from: None,
+        }
+    }
+
+    /// Create an ankan.
+    pub fn ankan(mentsu: Mentsu) -> Self {
+        Self {
+            kind: FuuroKind::Ankan,
+            mentsu,
+            called: None,
+            from: None,
+        }
+    }
+
+    /// Return if made by calling others, which breaks menzen. Only ankan is not.
+    pub fn is_open(&self) -> bool {
+        self.kind != FuuroKind::Ankan
+    }
+
+    /// Return all hai in the fuuro.
+    pub fn hai_vec(&self) -> Vec<Hai> {
+        self.mentsu.hai_vec()
+    }
+
+    /// Return the fuuro with red five replaced by normal five.
+    pub fn normalize(&self) -> Fuuro {
+        Fuuro {
+            mentsu: self.mentsu.normalize(),
+            called: self.called.map(|hai| hai.normalize()),
+            ..*self
+        }
+    }
+
+    /// Return compact notation like `[12<3m]` and `(1111z)`, which is parsed back to the
+    /// same fuuro, except `called` without `from`.
+    pub fn to_compact_string(&self) -> String {
+        let (open, close) = if self.is_open() {
+            ("[", "]")
+        } else {
+            ("(", ")")
+        };
+        format!(
+            "{}{}{}",
+            open,
+            Hai::compact_string(&self.marked_hai_vec()),
+            close
+        )
+    }
+
+    /// Return all hai with marks of the called hai and the added hai of kakan.
+    fn marked_hai_vec(&self) -> Vec<(&'static str, Hai)> {
+        let hai_vec = self.hai_vec();
+        let called_index = match (self.called, self.from) {
+            (Some(called), Some(_)) => hai_vec.iter().position(|hai| *hai == called),
+            _ => None,
+        };
+        let added_index = match (self.kind, called_index) {
+            (FuuroKind::Kakan, Some(called_index)) => Some(if called_index == 0 {
+                hai_vec.len() - 1
+            } else {
+                0
+            }),
+            _ => None,
+        };
+
+        hai_vec
+            .into_iter()
+            .enumerate()
+            .map(|(index, hai)| {
+                let mark = if Some(index) == called_index {
+                    match self.from {
+                        Some(Tacha::Kamicha) => "<",
+                        Some(Tacha::Toimen) => "^",
+                        Some(Tacha::Shimocha) => ">",
+                        None => "",
+                    }
+                } else if Some(index) == added_index {
+                    "+"
+                } else {
+                    ""
+                };
+                (mark, hai)
+            })
+            .collect()
+    }
+}
+
+/// Print in the same way as input, like `[1m2m<3m]` and `(1z1z1z1z)`.
+impl StyledDisplay for Fuuro {
+    fn fmt_styled(&self, f: &mut std::fmt::Formatter<'_>, style: HaiStyle) -> std::fmt::Result {
+        write!(f, "{}", if self.is_open() { "[" } else { "(" })?;
+        for (mark, hai) in self.marked_hai_vec() {
+            write!(f, "{}{}", mark, hai.styled(style))?;
+        }
+        write!(f, "{}", if self.is_open() { "]" } else { ")" })
+    }
+}
+
+impl std::fmt::Display for Fuuro {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_styled(f, HaiStyle::Text)
+    }
+}
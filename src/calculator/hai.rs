@@ -1,4 +1,5 @@
 
+use super::{HaiStyle, StyledDisplay};
 use std::collections::BTreeSet;
 
 /// Type of hai(tile).
@@ -9,13 +10,24 @@ use std::collections::BTreeSet;
 /// * Pinzu: 筒子
 /// * Souzu: 索子
 /// * Jihai: 字牌
+/// * aka: 赤
 ///
 /// # Abbreviation
 /// * Manzu: m
 /// * Pinzu: p
 /// * Souzu: s
 /// * Jihai: z
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// # Alias
+/// Jihai can also be written in one character as tenhou.net and mjai do, `E` `S` `W` `N`
+/// for 1\~4z and `P` `F` `C` for 5\~7z. Any hai can be written as Unicode mahjong tile
+/// from `🀀` to `🀡`.
+///
+/// # Aka
+/// Red five is `Manzu(0)`, `Pinzu(0)` or `Souzu(0)`, written as `0m`, `0p` and `0s`. It is
+/// a different hai from normal five, but sorted right after it and works as five everywhere
+/// else, like `next` and `previous`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Hai {
     Manzu(u8),
     Pinzu(u8),
@@ -25,25 +37,41 @@ pub enum Hai {
 
 impl Hai {
 
-    /// Return if valid -- it means 1\~9m, 1\~9p, 1\~9s, 1\~7z on 4-players mode
-    /// and 1m, 9m, 1\~9p, 1\~9s, 1\~7z on 3-players mode.
-    pub fn is_valid(&self) -> bool {
+    /// Return if valid -- it means 0\~9m, 0\~9p, 0\~9s, 1\~7z on 4-players mode
+    /// and 1m, 9m, 0\~9p, 0\~9s, 1\~7z on 3-players mode.
+    ///
+    /// # Parameters
+    /// * player_number: Number of players. No 2\~8m and 0m on 3-players mode.
+    pub fn is_valid(&self, player_number: u8) -> bool {
         match self {
-            Hai::Manzu(1..=9)
-            | Hai::Pinzu(1..=9)
-            | Hai::Souzu(1..=9)
-            | Hai::Jihai(1..=7) => true,
+            Hai::Manzu(0) | Hai::Manzu(2..=8) => player_number != 3,
+            Hai::Manzu(1 | 9) | Hai::Pinzu(0..=9) | Hai::Souzu(0..=9) | Hai::Jihai(1..=7) => true,
             _ => false,
         }
     }
 
+    /// Return if self is red five.
+    pub fn is_aka(&self) -> bool {
+        matches!(self, Hai::Manzu(0) | Hai::Pinzu(0) | Hai::Souzu(0))
+    }
+
+    /// Return normal five for red five, otherwise self.
+    pub fn normalize(&self) -> Hai {
+        match self {
+            Hai::Manzu(0) => Hai::Manzu(5),
+            Hai::Pinzu(0) => Hai::Pinzu(5),
+            Hai::Souzu(0) => Hai::Souzu(5),
+            _ => *self,
+        }
+    }
+
     /// Return ture when **all** hai in iterator is valid. Otherwise return false.
-    pub fn check_iter_valid<'a, T>(iter: T) -> bool
+    pub fn check_iter_valid<'a, T>(iter: T, player_number: u8) -> bool
     where
         T: Iterator<Item = &'a Self>,
     {
         for hai in iter {
-            if !hai.is_valid() {
+            if !hai.is_valid(player_number) {
                 return false;
             }
         }
@@ -68,12 +96,131 @@ impl Hai {
         yaochuupai_vec
     }
 
-    /// Return a BTreeSet including all valid types of hai.
-    pub fn all_type() -> BTreeSet<Hai> {
+    /// Return the number part of hai, like 3 for 3p and 5 for 5z. Red five is 5.
+    pub fn number(&self) -> u8 {
+        match &self.normalize() {
+            Hai::Manzu(num) | Hai::Pinzu(num) | Hai::Souzu(num) | Hai::Jihai(num) => *num,
+        }
+    }
+
+    /// Return the abbreviation of the type, like `p` for 3p.
+    pub fn type_char(&self) -> char {
+        match self {
+            Hai::Manzu(_) => 'm',
+            Hai::Pinzu(_) => 'p',
+            Hai::Souzu(_) => 's',
+            Hai::Jihai(_) => 'z',
+        }
+    }
+
+    /// Return hai written in one character, honor letters like `E` for 1z or Unicode mahjong
+    /// tiles like `🀇` for 1m. `None` for any other character.
+    pub fn from_alias(chr: char) -> Option<Hai> {
+        match chr {
+            'E' => Some(Hai::Jihai(1)),
+            'S' => Some(Hai::Jihai(2)),
+            'W' => Some(Hai::Jihai(3)),
+            'N' => Some(Hai::Jihai(4)),
+            'P' => Some(Hai::Jihai(5)),
+            'F' => Some(Hai::Jihai(6)),
+            'C' => Some(Hai::Jihai(7)),
+            '\u{1F000}'..='\u{1F021}' => {
+                // Winds, then chun, hatsu, haku, then manzu, souzu and pinzu.
+                let offset = (chr as u32 - 0x1F000) as u8;
+                Some(match offset {
+                    0..=3 => Hai::Jihai(offset + 1),
+                    4..=6 => Hai::Jihai(11 - offset),
+                    7..=15 => Hai::Manzu(offset - 6),
+                    16..=24 => Hai::Souzu(offset - 15),
+                    _ => Hai::Pinzu(offset - 24),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Return Unicode mahjong tile of hai, the same for red five and normal five. `�` for
+    /// invalid hai.
+    pub fn unicode_char(&self) -> char {
+        if !self.is_valid(4) {
+            return char::REPLACEMENT_CHARACTER;
+        }
+        let offset = match self.normalize() {
+            Hai::Jihai(num @ 1..=4) => num - 1,
+            Hai::Jihai(num) => 11 - num,
+            Hai::Manzu(num) => num + 6,
+            Hai::Souzu(num) => num + 15,
+            Hai::Pinzu(num) => num + 24,
+        };
+        char::from_u32(0x1F000 + offset as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
+    /// Write hai in compact notation, each with a mark before it, like `12<3m4p` for
+    /// 1m, 2m, `<`3m and 4p. The type is written once for hai of the same type in a row.
+    pub(super) fn compact_string(marked_hai_vec: &[(&str, Hai)]) -> String {
+        let mut output = String::new();
+        for (index, (mark, hai)) in marked_hai_vec.iter().enumerate() {
+            let (Hai::Manzu(num) | Hai::Pinzu(num) | Hai::Souzu(num) | Hai::Jihai(num)) = hai;
+            output += mark;
+            output += &num.to_string();
+            if marked_hai_vec
+                .get(index + 1)
+                .is_none_or(|(_, next)| next.type_char() != hai.type_char())
+            {
+                output.push(hai.type_char());
+            }
+        }
+        output
+    }
+
+    /// Return if self is jihai -- 1\~7z.
+    pub fn is_jihai(&self) -> bool {
+        matches!(self, Hai::Jihai(_))
+    }
+
+    /// Return if self is routouhai -- 1m, 9m, 1p, 9p, 1s, 9s.
+    ///
+    /// # Japanese
+    /// * routouhai: 老頭牌
+    pub fn is_routouhai(&self) -> bool {
+        !self.is_jihai() && (self.number() == 1 || self.number() == 9)
+    }
+
+    /// Return if self is yaochuupai -- 1m, 9m, 1p, 9p, 1s, 9s, 1\~7z.
+    ///
+    /// # Japanese
+    /// * yaochuupai: 幺九牌
+    pub fn is_yaochuupai(&self) -> bool {
+        self.is_jihai() || self.is_routouhai()
+    }
+
+    /// Return if self is kazehai -- 1\~4z.
+    ///
+    /// # Japanese
+    /// * kazehai: 風牌
+    pub fn is_kazehai(&self) -> bool {
+        matches!(self, Hai::Jihai(1..=4))
+    }
+
+    /// Return if self is sangenpai -- 5\~7z.
+    ///
+    /// # Japanese
+    /// * sangenpai: 三元牌
+    pub fn is_sangenpai(&self) -> bool {
+        matches!(self, Hai::Jihai(5..=7))
+    }
+
+    /// Return a BTreeSet including all valid types of hai, without red five.
+    ///
+    /// # Parameters
+    /// * player_number: Number of players. No 2\~8m on 3-players mode.
+    pub fn all_type(player_number: u8) -> BTreeSet<Hai> {
         let mut all_hai_type = BTreeSet::new();
 
         for index in 1u8..=9u8 {
-            all_hai_type.insert(Hai::Manzu(index));
+            if Hai::Manzu(index).is_valid(player_number) {
+                all_hai_type.insert(Hai::Manzu(index));
+            }
         }
 
         for index in 1u8..=9u8 {
@@ -91,18 +238,28 @@ impl Hai {
     /// Return previous hai. It means, like 1m for 2m.
     ///
     /// # Parameters
-    /// * player_number: Number of players. No 2\~8m on 3-players mode.
+    /// * player_number: Number of players. No 2\~8m on 3-players mode, so `Manzu(9).previous()`
+    ///   will be `Some(Manzu(1))` if dora_loop, otherwise `None`.
     /// * dora_loop: If true, `Manzu(1).previous()`, `Pinzu(1).previous()` and
-    /// `Souzu(1).previous()` will be `Some(Manzu(9))`, `Some(Pinzu(9))` and
-    /// `Some(souzu(9))`, `Jihai(1).previous()` will be `Some(Jihai(4))`,
-    /// `Jihai(5).previous()` will be `Some(Jihai(7))`. Otherwise, `Manzu(1).previous()`,
-    /// `Pinzu(1).previous()`, `Souzu(1).previous()` and `Jihai(1).previous()`
-    /// will all be `None`.
+    ///   `Souzu(1).previous()` will be `Some(Manzu(9))`, `Some(Pinzu(9))` and
+    ///   `Some(souzu(9))`, `Jihai(1).previous()` will be `Some(Jihai(4))`,
+    ///   `Jihai(5).previous()` will be `Some(Jihai(7))`. Otherwise, `Manzu(1).previous()`,
+    ///   `Pinzu(1).previous()`, `Souzu(1).previous()` and `Jihai(1).previous()`
+    ///   will all be `None`.
     ///
     /// # Japanese
     /// * dora: ドラ
-    pub fn previous(&self, dora_loop: bool) -> Option<Hai> {
-        match self {
+    pub fn previous(&self, player_number: u8, dora_loop: bool) -> Option<Hai> {
+        if player_number == 3 {
+            match self {
+                Hai::Manzu(1) if dora_loop => return Some(Hai::Manzu(9)),
+                Hai::Manzu(9) if dora_loop => return Some(Hai::Manzu(1)),
+                Hai::Manzu(_) => return None,
+                _ => (),
+            }
+        }
+
+        match &self.normalize() {
             Hai::Manzu(num) =>{
                 if *num != 1 {
                     Some(Hai::Manzu(*num - 1))
@@ -151,18 +308,28 @@ impl Hai {
     /// Return next hai. It means, like 2m for 1m.
     ///
     /// # Parameters
-    /// * player_number: Number of players. No 2\~8m on 3-players mode.
+    /// * player_number: Number of players. No 2\~8m on 3-players mode, so `Manzu(1).next()`
+    ///   will be `Some(Manzu(9))` if dora_loop, otherwise `None`.
     /// * dora_loop: If true, `Manzu(9).next()`, `Pinzu(9).next()` and
-    /// `Souzu(9).next()` will be `Some(Manzu(1))`, `Some(Pinzu(1))` and
-    /// `Some(souzu(1))`, `Jihai(4).next()` will be `Some(Jihai(1))`,
-    /// `Jihai(7).next()` will be `Some(Jihai(5))`. Otherwise, `Manzu(1).next()`,
-    /// `Pinzu(9).next()`, `Souzu(9).next()` and `Jihai(7).next()` will
-    /// all be `None`.
+    ///   `Souzu(9).next()` will be `Some(Manzu(1))`, `Some(Pinzu(1))` and
+    ///   `Some(souzu(1))`, `Jihai(4).next()` will be `Some(Jihai(1))`,
+    ///   `Jihai(7).next()` will be `Some(Jihai(5))`. Otherwise, `Manzu(1).next()`,
+    ///   `Pinzu(9).next()`, `Souzu(9).next()` and `Jihai(7).next()` will
+    ///   all be `None`.
     ///
     /// # Japanese
     /// * dora: ドラ
-    pub fn next(&self, dora_loop: bool) -> Option<Hai> {
-        match self {
+    pub fn next(&self, player_number: u8, dora_loop: bool) -> Option<Hai> {
+        if player_number == 3 {
+            match self {
+                Hai::Manzu(1) if dora_loop => return Some(Hai::Manzu(9)),
+                Hai::Manzu(9) if dora_loop => return Some(Hai::Manzu(1)),
+                Hai::Manzu(_) => return None,
+                _ => (),
+            }
+        }
+
+        match &self.normalize() {
             Hai::Manzu(num) => {
                 if *num != 9 {
                     Some(Hai::Manzu(*num + 1))
@@ -209,17 +376,93 @@ impl Hai {
     }
 }
 
+impl Hai {
+    fn sort_key(&self) -> (u8, u8, bool) {
+        let suit = match self {
+            Hai::Manzu(_) => 0,
+            Hai::Pinzu(_) => 1,
+            Hai::Souzu(_) => 2,
+            Hai::Jihai(_) => 3,
+        };
+        (suit, self.number(), self.is_aka())
+    }
+}
+
+impl PartialOrd for Hai {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hai {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
 impl std::fmt::Display for Hai {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Hai::Manzu(num) => format!("{}m", num),
-                Hai::Pinzu(num) => format!("{}p", num),
-                Hai::Souzu(num) => format!("{}s", num),
-                Hai::Jihai(num) => format!("{}z", num),
-            }
-        )
+        self.fmt_styled(f, HaiStyle::Text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_on_3_players() {
+        for hai in [
+            Hai::Manzu(1),
+            Hai::Manzu(9),
+            Hai::Pinzu(0),
+            Hai::Souzu(5),
+            Hai::Jihai(7),
+        ] {
+            assert!(hai.is_valid(3), "{}", hai);
+            assert!(hai.is_valid(4), "{}", hai);
+        }
+        for hai in [Hai::Manzu(0), Hai::Manzu(2), Hai::Manzu(5), Hai::Manzu(8)] {
+            assert!(!hai.is_valid(3), "{}", hai);
+            assert!(hai.is_valid(4), "{}", hai);
+        }
+        assert!(!Hai::Jihai(8).is_valid(3));
+        assert_eq!(Hai::all_type(4).len(), 34);
+        assert_eq!(Hai::all_type(3).len(), 27);
+    }
+
+    #[test]
+    fn dora_loop_on_3_players() {
+        assert_eq!(Hai::Manzu(1).next(3, true), Some(Hai::Manzu(9)));
+        assert_eq!(Hai::Manzu(9).next(3, true), Some(Hai::Manzu(1)));
+        assert_eq!(Hai::Manzu(1).previous(3, true), Some(Hai::Manzu(9)));
+        assert_eq!(Hai::Manzu(9).previous(3, true), Some(Hai::Manzu(1)));
+        assert_eq!(Hai::Manzu(1).next(3, false), None);
+        assert_eq!(Hai::Manzu(9).previous(3, false), None);
+
+        assert_eq!(Hai::Manzu(1).next(4, true), Some(Hai::Manzu(2)));
+        assert_eq!(Hai::Manzu(9).next(4, true), Some(Hai::Manzu(1)));
+        assert_eq!(Hai::Pinzu(9).next(3, true), Some(Hai::Pinzu(1)));
+        assert_eq!(Hai::Jihai(4).next(3, true), Some(Hai::Jihai(1)));
+    }
+
+    #[test]
+    fn from_alias() {
+        assert_eq!(Hai::from_alias('E'), Some(Hai::Jihai(1)));
+        assert_eq!(Hai::from_alias('C'), Some(Hai::Jihai(7)));
+        assert_eq!(Hai::from_alias('\u{1F004}'), Some(Hai::Jihai(7)));
+        assert_eq!(Hai::from_alias('\u{1F006}'), Some(Hai::Jihai(5)));
+        assert_eq!(Hai::from_alias('\u{1F007}'), Some(Hai::Manzu(1)));
+        assert_eq!(Hai::from_alias('\u{1F010}'), Some(Hai::Souzu(1)));
+        assert_eq!(Hai::from_alias('\u{1F021}'), Some(Hai::Pinzu(9)));
+        for chr in ['e', 'm', '1', ' ', '\u{1F022}', '\u{1F02B}'] {
+            assert_eq!(Hai::from_alias(chr), None, "{:?}", chr);
+        }
+
+        // Every Unicode tile is a different valid hai.
+        let all_hai: BTreeSet<Hai> = ('\u{1F000}'..='\u{1F021}')
+            .map(|chr| Hai::from_alias(chr).unwrap())
+            .collect();
+        assert_eq!(all_hai, Hai::all_type(4));
     }
 }
@@ -0,0 +1,265 @@
+use super::{Error, Hai, HaiCounts, HaiStyle, StyledDisplay, Taku, Tehai, Ukeire};
+
+/// Hai not reducing shanten but making more ukeire, with the best sutehai after drawing.
+///
+/// # Japanese
+/// * kairyouhai: 改良牌
+/// * sutehai: 捨て牌
+///
+/// # Member
+/// * hai: the hai to be drawn.
+/// * nokori: how many `hai` are left.
+/// * sutehai: the hai to be discarded after drawing, keeping shanten.
+/// * ukeire: the number of ukeire after discarding `sutehai`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Kairyouhai {
+    pub hai: Hai,
+    pub nokori: u8,
+    pub sutehai: Hai,
+    pub ukeire: usize,
+}
+
+/// Two-step ukeire of a 3*k+1 tehai.
+///
+/// # Japanese
+/// * kairyou: 改良
+///
+/// # Member
+/// * ukeire: hai reducing shanten right now.
+/// * kairyouhai: hai making more ukeire without reducing shanten, sorted by hai.
+/// * average: expected number of ukeire after drawing any hai not in `ukeire`, taking the
+///   best sutehai each time. Same as the number of `ukeire` if nothing can be drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kairyou {
+    pub ukeire: Ukeire,
+    pub kairyouhai: Vec<Kairyouhai>,
+    pub average: f64,
+}
+
+impl Tehai {
+    /// Analyze a 3*k+1 tehai for ukeire and kairyouhai.
+    ///
+    /// Every hai not reducing shanten is tried to be drawn, then every other hai is tried to
+    /// be discarded, and the one left with most ukeire at the same shanten is taken.
+    pub fn kairyou(&self, taku: &Taku) -> Result<Kairyou, Error> {
+        let ukeire = self.ukeire(taku)?;
+
+        let tehai = self.normalize();
+        let mut used_hai = HaiCounts::from(&tehai.all_hai()[..]);
+        for hai in taku.visible_hai() {
+            used_hai.add(&hai);
+        }
+        let mut used_hai = used_hai.normalized();
+        let mut counts = tehai.counts().normalized();
+        let all_type: Vec<Hai> = Hai::all_type(self.player_number).into_iter().collect();
+        let nokori = ukeire.nokori();
+
+        let mut kairyouhai = vec![];
+        let mut drawn_number = 0;
+        let mut drawn_ukeire = 0;
+        for hai in &all_type {
+            let index = hai.index();
+            if counts[index] >= 4 || used_hai[index] >= 4 {
+                continue;
+            }
+            counts[index] += 1;
+            used_hai[index] += 1;
+
+            if tehai.counts_shanten(&counts)? == ukeire.shanten {
+                // Discarding the drawn hai itself gets back to the current ukeire.
+                let mut best = (nokori, *hai);
+                for sutehai in &all_type {
+                    if sutehai == hai || counts[sutehai.index()] == 0 {
+                        continue;
+                    }
+                    counts[sutehai.index()] -= 1;
+                    let (shanten, number) =
+                        tehai.counts_ukeire(&mut counts, &used_hai, &all_type)?;
+                    counts[sutehai.index()] += 1;
+                    if shanten == ukeire.shanten && number > best.0 {
+                        best = (number, *sutehai);
+                    }
+                }
+
+                let hai_nokori = 4 - (used_hai[index] - 1);
+                drawn_number += hai_nokori as usize;
+                drawn_ukeire += hai_nokori as usize * best.0;
+                if best.0 > nokori {
+                    kairyouhai.push(Kairyouhai {
+                        hai: *hai,
+                        nokori: hai_nokori,
+                        sutehai: best.1,
+                        ukeire: best.0,
+                    });
+                }
+            }
+
+            counts[index] -= 1;
+            used_hai[index] -= 1;
+        }
+
+        let average = if drawn_number == 0 {
+            nokori as f64
+        } else {
+            drawn_ukeire as f64 / drawn_number as f64
+        };
+        Ok(Kairyou {
+            ukeire,
+            kairyouhai,
+            average,
+        })
+    }
+
+    /// Analyze a 3*k+2 tehai for ukeire and kairyouhai of every sutehai.
+    ///
+    /// The sutehai is counted in kawa, so furiten is checked with it. Sorted by shanten,
+    /// then by the number of ukeire and the average after drawing, better first.
+    pub fn kairyou_conditions(&self, taku: &Taku) -> Result<Vec<(Hai, Kairyou)>, Error> {
+        if self.juntehai.len() % 3 != 2 {
+            return Err(Error::WrongHaiNumber {
+                remainder: 2,
+                found: self.juntehai.len(),
+            });
+        }
+        self.check_taku(taku)?;
+
+        let mut sutehai_vec: Vec<Hai> = self.juntehai.iter().map(|hai| hai.normalize()).collect();
+        sutehai_vec.sort();
+        sutehai_vec.dedup();
+
+        let mut conditions = vec![];
+        for sutehai in sutehai_vec {
+            // Discard aka only if no normal one.
+            let position = self
+                .juntehai
+                .iter()
+                .position(|hai| *hai == sutehai)
+                .or_else(|| {
+                    self.juntehai
+                        .iter()
+                        .position(|hai| hai.normalize() == sutehai)
+                })
+                .ok_or(Error::Logic("Sutehai not found in juntehai."))?;
+            let mut tehai = self.clone();
+            let sutehai = tehai.juntehai.remove(position);
+            let mut taku = taku.clone();
+            taku.kawa.push(sutehai);
+            conditions.push((sutehai, tehai.kairyou(&taku)?));
+        }
+
+        conditions.sort_by(|(_, lhs), (_, rhs)| {
+            lhs.ukeire
+                .shanten
+                .cmp(&rhs.ukeire.shanten)
+                .then(rhs.ukeire.nokori().cmp(&lhs.ukeire.nokori()))
+                .then(rhs.average.total_cmp(&lhs.average))
+        });
+        Ok(conditions)
+    }
+
+    /// Return shanten and the number of ukeire of 3*k+1 normalized counts as juntehai, with
+    /// all hai used in `used_hai`.
+    fn counts_ukeire(
+        &self,
+        counts: &mut [u8; 34],
+        used_hai: &[u8; 34],
+        all_type: &[Hai],
+    ) -> Result<(i32, usize), Error> {
+        let mut min_shanten = None;
+        let mut number = 0;
+        for hai in all_type {
+            let index = hai.index();
+            if counts[index] >= 4 {
+                continue;
+            }
+            counts[index] += 1;
+            let shanten = self.counts_shanten(counts);
+            counts[index] -= 1;
+            let shanten = shanten? + 1;
+
+            let nokori = 4usize.saturating_sub(used_hai[index] as usize);
+            match min_shanten {
+                Some(min) if shanten > min => {}
+                Some(min) if shanten == min => number += nokori,
+                _ => {
+                    min_shanten = Some(shanten);
+                    number = nokori;
+                }
+            }
+        }
+        Ok((
+            min_shanten.ok_or(Error::Logic("No hai can be drawn."))?,
+            number,
+        ))
+    }
+}
+
+/// Print like `摸 1m 4m  共5枚`, then each kairyouhai like `改良 3p(4) 打 9s 共20枚`, and
+/// the average like `改良後平均 12.34枚`.
+impl StyledDisplay for Kairyou {
+    fn fmt_styled(&self, f: &mut std::fmt::Formatter<'_>, style: HaiStyle) -> std::fmt::Result {
+        write!(f, "{}", self.ukeire.styled(style))?;
+        for kairyouhai in &self.kairyouhai {
+            write!(
+                f,
+                "\n改良 {}({}) 打 {} 共{}枚",
+                kairyouhai.hai.styled(style),
+                kairyouhai.nokori,
+                kairyouhai.sutehai.styled(style),
+                kairyouhai.ukeire
+            )?;
+        }
+        write!(f, "\n改良後平均 {:.2}枚", self.average)
+    }
+}
+
+impl std::fmt::Display for Kairyou {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_styled(f, HaiStyle::Text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iishanten() {
+        let tehai = Tehai::new("1123z".to_string()).unwrap();
+        let kairyou = tehai.kairyou(&Taku::default()).unwrap();
+        // 1z of 2 left, 2z and 3z of 3 left.
+        assert_eq!(kairyou.ukeire.shanten, 1);
+        assert_eq!(kairyou.ukeire.nokori(), 8);
+
+        // Drawing any suuhai and discarding 2z, like 113z1m waiting for 1z, 3z, 1m, 2m, 3m.
+        assert_eq!(kairyou.kairyouhai.len(), 27);
+        assert_eq!(
+            kairyou.kairyouhai[0],
+            Kairyouhai {
+                hai: Hai::Manzu(1),
+                nokori: 4,
+                sutehai: Hai::Jihai(2),
+                ukeire: 16,
+            }
+        );
+        assert_eq!(kairyou.kairyouhai[1].ukeire, 20);
+        assert_eq!(kairyou.kairyouhai[2].ukeire, 24);
+
+        // 16 jihai keeping 8, 24 of 1 or 9 making 16, 24 of 2 or 8 making 20, and 60 of 3~7
+        // making 24.
+        let average = (16 * 8 + 24 * 16 + 24 * 20 + 60 * 24) as f64 / 124.0;
+        assert!((kairyou.average - average).abs() < 1e-9);
+    }
+
+    #[test]
+    fn conditions() {
+        let tehai = Tehai::new("11234z".to_string()).unwrap();
+        let conditions = tehai.kairyou_conditions(&Taku::default()).unwrap();
+        let sutehai: Vec<Hai> = conditions.iter().map(|(hai, _)| *hai).collect();
+        assert_eq!(sutehai[3], Hai::Jihai(1));
+        assert!(conditions[..3]
+            .iter()
+            .all(|(_, kairyou)| kairyou.ukeire.shanten == 1 && kairyou.ukeire.nokori() == 8));
+        assert_eq!(conditions[3].1.ukeire.shanten, 2);
+    }
+}
@@ -1,8 +1,30 @@
+mod agari;
 mod block;
+mod counts;
+mod error;
+mod fuuro;
 mod hai;
+mod kairyou;
+mod score;
+mod shanten;
+mod style;
+mod taku;
 mod tehai;
+mod ukeire;
+mod yaku;
+mod yama;
 
-
+pub use agari::{AgariDecomposer, Machi};
 pub use block::{Mentsu, Taatsu, Toitsu, Ukihai};
+pub use counts::{HaiCounts, HAI_INDEX_NUMBER};
+pub use error::Error;
+pub use fuuro::{Fuuro, FuuroKind, Tacha};
 pub use hai::Hai;
-pub use tehai::{MachiCondition, Tehai};
+pub use kairyou::{Kairyou, Kairyouhai};
+pub use score::{Limit, Shiharai, Tensuu};
+pub use style::{HaiStyle, Styled, StyledDisplay};
+pub use taku::Taku;
+pub use tehai::{Hourakei, MachiCondition, Tehai};
+pub use ukeire::Ukeire;
+pub use yaku::{AgariCondition, Yaku};
+pub use yama::Yama;
@@ -0,0 +1,444 @@
+use super::agari::{AgariDecomposer, Machi};
+use super::tehai::Hourakei;
+use super::{AgariCondition, Error, Hai, Mentsu, Tehai, Yaku};
+
+/// Limit of points when han is high enough.
+///
+/// # Japanese
+/// * Mangan: 満貫
+/// * Haneman: 跳満
+/// * Baiman: 倍満
+/// * Sanbaiman: 三倍満
+/// * KazoeYakuman: 数え役満
+/// * Yakuman: 役満
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Limit {
+    Mangan,
+    Haneman,
+    Baiman,
+    Sanbaiman,
+    KazoeYakuman,
+    Yakuman(u8),
+}
+
+/// How the points are paid, honba included.
+///
+/// # Japanese
+/// * Shiharai: 支払い
+/// * Ron: 栄和, paid by the player who discarded agari hai.
+/// * TsumoOya: 親の自摸和, paid by every ko.
+/// * TsumoKo: 子の自摸和, paid by oya and by every other ko.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Shiharai {
+    Ron(u32),
+    TsumoOya(u32),
+    TsumoKo { oya: u32, ko: u32 },
+}
+
+/// Points of a winning tehai.
+///
+/// # Japanese
+/// * Tensuu: 点数
+/// * fu: 符
+/// * han: 飜
+///
+/// # Member
+/// * decomposer: the reading of tehai chosen.
+/// * yaku_vec: all yaku of the reading.
+/// * menzen: if the tehai is menzen, deciding han of each yaku.
+/// * dora: number of dora, including uradora.
+/// * aka: number of red five, each one counted as a dora.
+/// * nukidora: number of nukidora, each one counted as a dora. Only on 3-players mode.
+/// * han: han of yaku and dora.
+/// * fu: fu rounded up to 10, or 25 for chiitoitsu.
+/// * limit: limit reached, if any.
+/// * shiharai: how the points are paid.
+/// * kyoutaku: points of riichi sticks taken by the winner.
+/// * player_number: number of players. On 3-players mode, tsumo is paid by only 2 players
+///   without the missing ko's share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tensuu {
+    pub decomposer: AgariDecomposer,
+    pub yaku_vec: Vec<Yaku>,
+    pub menzen: bool,
+    pub dora: u8,
+    pub aka: u8,
+    pub nukidora: u8,
+    pub han: u8,
+    pub fu: u8,
+    pub limit: Option<Limit>,
+    pub shiharai: Shiharai,
+    pub kyoutaku: u32,
+    pub player_number: u8,
+}
+
+impl AgariDecomposer {
+    /// Calculate fu of this reading.
+    ///
+    /// # Parameters
+    /// * pinfu: If true, tsumo gives no fu.
+    pub fn fu(&self, condition: &AgariCondition, pinfu: bool) -> u8 {
+        match self.hourakei {
+            Hourakei::Chiitoitsu => return 25,
+            Hourakei::Kokushimusou => return 0,
+            Hourakei::Mentsute => (),
+        }
+
+        let menzen = self.menzen();
+        let mut fu = 20;
+
+        if condition.tsumo {
+            if !pinfu {
+                fu += 2;
+            }
+        } else if menzen {
+            fu += 10;
+        }
+
+        if let Machi::Kanchan | Machi::Penchan | Machi::Tanki = self.machi {
+            fu += 2;
+        }
+
+        let jantou = self.toitsu_vec[0].0;
+        if jantou.is_sangenpai() {
+            fu += 2;
+        }
+        if jantou == condition.bakaze {
+            fu += 2;
+        }
+        if jantou == condition.jikaze {
+            fu += 2;
+        }
+
+        let mentsu_fu = |mentsu: &Mentsu, concealed: bool| -> u8 {
+            let (hai, base) = match *mentsu {
+                Mentsu::Juntsu(..) => return 0,
+                Mentsu::Koutsu(hai) => (hai, 2),
+                Mentsu::Kantsu(hai) => (hai, 8),
+            };
+            base * if concealed { 2 } else { 1 } * if hai.is_yaochuupai() { 2 } else { 1 }
+        };
+        for mentsu in &self.mentsu_vec {
+            // Koutsu completed by ron is not concealed.
+            let concealed = condition.tsumo
+                || self.machi != Machi::Shanpon
+                || *mentsu != Mentsu::Koutsu(self.agari_hai);
+            fu += mentsu_fu(mentsu, concealed);
+        }
+        for fuuro in &self.fuuro {
+            fu += mentsu_fu(&fuuro.mentsu, !fuuro.is_open());
+        }
+
+        // Open pinfu shape is 30 fu.
+        if fu == 20 && !menzen {
+            fu = 30;
+        }
+
+        fu.div_ceil(10) * 10
+    }
+}
+
+impl Tensuu {
+    /// Calculate points of a reading.
+    ///
+    /// Return `None` if no yaku. Dora is not yaku.
+    pub fn new(
+        decomposer: AgariDecomposer,
+        tehai: &Tehai,
+        condition: &AgariCondition,
+    ) -> Option<Self> {
+        let yaku_vec = Yaku::judge(&decomposer, condition);
+        if yaku_vec.is_empty() {
+            return None;
+        }
+
+        let menzen = decomposer.menzen();
+        let fu = decomposer.fu(condition, yaku_vec.contains(&Yaku::Pinfu));
+        let yakuman: u8 = yaku_vec.iter().map(|yaku| yaku.yakuman()).sum();
+        let (han, dora, aka, nukidora) = if yakuman > 0 {
+            (13 * yakuman, 0, 0, 0)
+        } else {
+            let han: u8 = yaku_vec.iter().map(|yaku| yaku.han(menzen)).sum();
+            let mut dora = tehai.dora(&condition.dora_hyouji);
+            if condition.riichi || condition.double_riichi {
+                dora += tehai.dora(&condition.uradora_hyouji);
+            }
+            let aka = tehai.aka();
+            (han + dora + aka + tehai.nukidora, dora, aka, tehai.nukidora)
+        };
+
+        let limit = if yakuman > 0 {
+            Some(Limit::Yakuman(yakuman))
+        } else if han >= 13 {
+            Some(Limit::KazoeYakuman)
+        } else if han >= 11 {
+            Some(Limit::Sanbaiman)
+        } else if han >= 8 {
+            Some(Limit::Baiman)
+        } else if han >= 6 {
+            Some(Limit::Haneman)
+        } else if han >= 5 || (fu as u32) << (han + 2) > 2000 {
+            Some(Limit::Mangan)
+        } else {
+            None
+        };
+
+        // Basic points.
+        let kihon = match limit {
+            Some(Limit::Yakuman(multiple)) => 8000 * multiple as u32,
+            Some(Limit::KazoeYakuman) => 8000,
+            Some(Limit::Sanbaiman) => 6000,
+            Some(Limit::Baiman) => 4000,
+            Some(Limit::Haneman) => 3000,
+            Some(Limit::Mangan) => 2000,
+            None => (fu as u32) << (han + 2),
+        };
+        let round_up = |points: u32| points.div_ceil(100) * 100;
+        let honba = condition.honba as u32;
+
+        let shiharai = match (condition.tsumo, condition.is_oya()) {
+            (false, true) => Shiharai::Ron(round_up(kihon * 6) + 300 * honba),
+            (false, false) => Shiharai::Ron(round_up(kihon * 4) + 300 * honba),
+            (true, true) => Shiharai::TsumoOya(round_up(kihon * 2) + 100 * honba),
+            (true, false) => Shiharai::TsumoKo {
+                oya: round_up(kihon * 2) + 100 * honba,
+                ko: round_up(kihon) + 100 * honba,
+            },
+        };
+
+        Some(Self {
+            decomposer,
+            yaku_vec,
+            menzen,
+            dora,
+            aka,
+            nukidora,
+            han,
+            fu,
+            limit,
+            shiharai,
+            kyoutaku: 1000 * condition.kyoutaku as u32,
+            player_number: tehai.player_number,
+        })
+    }
+
+    /// Return all points the winner gets, including honba and kyoutaku.
+    pub fn total(&self) -> u32 {
+        let ko_number = self.player_number as u32 - 1;
+        self.kyoutaku
+            + match self.shiharai {
+                Shiharai::Ron(points) => points,
+                Shiharai::TsumoOya(points) => points * ko_number,
+                Shiharai::TsumoKo { oya, ko } => oya + ko * (ko_number - 1),
+            }
+    }
+}
+
+impl Tehai {
+    /// Count dora in tehai from indicators, nukidora included. Red five is not counted here.
+    ///
+    /// # Japanese
+    /// * dora_hyouji: ドラ表示牌
+    pub fn dora(&self, dora_hyouji: &[Hai]) -> u8 {
+        let all_hai = self.all_hai();
+
+        let mut dora = 0;
+        for hyouji in dora_hyouji {
+            if let Some(dora_hai) = hyouji.next(self.player_number, true) {
+                dora += all_hai
+                    .iter()
+                    .filter(|hai| hai.normalize() == dora_hai)
+                    .count() as u8;
+            }
+        }
+        dora
+    }
+
+    /// Calculate points when winning with condition.
+    ///
+    /// If the tehai can be read in different ways, the reading with most points is chosen.
+    /// Return `None` if no yaku.
+    pub fn tensuu(&self, condition: &AgariCondition) -> Result<Option<Tensuu>, Error> {
+        let decomposers = self.agari_decompose(condition.agari_hai)?;
+        if decomposers.is_empty() {
+            return Err(Error::NotAgari);
+        }
+
+        let mut best: Option<Tensuu> = None;
+        for decomposer in decomposers {
+            if let Some(tensuu) = Tensuu::new(decomposer, self, condition) {
+                if best
+                    .as_ref()
+                    .is_none_or(|best| (tensuu.total(), tensuu.han) > (best.total(), best.han))
+                {
+                    best = Some(tensuu);
+                }
+            }
+        }
+
+        Ok(best)
+    }
+}
+
+impl std::fmt::Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Mangan => write!(f, "満貫"),
+            Limit::Haneman => write!(f, "跳満"),
+            Limit::Baiman => write!(f, "倍満"),
+            Limit::Sanbaiman => write!(f, "三倍満"),
+            Limit::KazoeYakuman => write!(f, "数え役満"),
+            Limit::Yakuman(1) => write!(f, "役満"),
+            Limit::Yakuman(multiple) => write!(f, "{}倍役満", multiple),
+        }
+    }
+}
+
+impl std::fmt::Display for Shiharai {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Shiharai::Ron(points) => write!(f, "{}点", points),
+            Shiharai::TsumoOya(points) => write!(f, "{}点オール", points),
+            Shiharai::TsumoKo { oya, ko } => write!(f, "{}-{}点", ko, oya),
+        }
+    }
+}
+
+impl std::fmt::Display for Tensuu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for yaku in &self.yaku_vec {
+            if yaku.is_yakuman() {
+                writeln!(f, "{} {}", yaku, Limit::Yakuman(yaku.yakuman()))?;
+            } else {
+                writeln!(f, "{} {}飜", yaku, yaku.han(self.menzen))?;
+            }
+        }
+        if self.dora > 0 {
+            writeln!(f, "ドラ {}飜", self.dora)?;
+        }
+        if self.aka > 0 {
+            writeln!(f, "赤ドラ {}飜", self.aka)?;
+        }
+        if self.nukidora > 0 {
+            writeln!(f, "抜きドラ {}飜", self.nukidora)?;
+        }
+
+        match self.limit {
+            Some(Limit::Yakuman(multiple)) => {
+                write!(f, "{} {}", Limit::Yakuman(multiple), self.shiharai)?
+            }
+            Some(limit) => write!(f, "{}飜 {} {}", self.han, limit, self.shiharai)?,
+            None => write!(f, "{}符 {}飜 {}", self.fu, self.han, self.shiharai)?,
+        }
+        if self.kyoutaku > 0 {
+            write!(f, " 供託{}点", self.kyoutaku)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tensuu(tehai: &str, player_number: u8, condition: &AgariCondition) -> Tensuu {
+        Tehai::with_player_number(tehai.to_string(), player_number)
+            .unwrap()
+            .tensuu(condition)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn fu() {
+        // 20 + 10 menzen ron + 2 tanki + 2 jikaze jantou + 8 yaochuupai ankou.
+        let condition = AgariCondition::new(Hai::Jihai(2), false);
+        let ron = tensuu("123m456p789s11122z", 4, &condition);
+        assert_eq!((ron.fu, ron.han), (50, 1));
+        assert_eq!(ron.shiharai, Shiharai::Ron(1600));
+
+        let condition = AgariCondition::new(Hai::Manzu(4), false);
+        let ron = tensuu("234m456p678s55m[234s]", 4, &condition);
+        assert_eq!(ron.yaku_vec, [Yaku::Tanyao]);
+        assert_eq!((ron.fu, ron.han), (30, 1));
+        assert_eq!(ron.shiharai, Shiharai::Ron(1000));
+
+        let condition = AgariCondition::new(Hai::Jihai(3), false);
+        let ron = tensuu("1199m1199p1155s33z", 4, &condition);
+        assert_eq!((ron.fu, ron.han), (25, 2));
+        assert_eq!(ron.shiharai, Shiharai::Ron(1600));
+    }
+
+    #[test]
+    fn limit() {
+        let mut condition = AgariCondition::new(Hai::Manzu(4), false);
+        condition.riichi = true;
+        condition.dora_hyouji = vec![Hai::Souzu(7)];
+        let tehai = "234567m345p678s55s";
+
+        // No kiriage mangan for 30 fu 4 han.
+        let ko = tensuu(tehai, 4, &condition);
+        assert_eq!((ko.fu, ko.han, ko.limit), (30, 4, None));
+        assert_eq!(ko.shiharai, Shiharai::Ron(7700));
+        condition.jikaze = Hai::Jihai(1);
+        let oya = tensuu(tehai, 4, &condition);
+        assert_eq!(oya.shiharai, Shiharai::Ron(11600));
+
+        // 40 fu 4 han is over 2000 basic points.
+        condition.jikaze = Hai::Jihai(2);
+        condition.agari_hai = Hai::Souzu(5);
+        let ko = tensuu("234567m345p678s55s", 4, &condition);
+        assert_eq!((ko.fu, ko.han), (40, 3));
+        condition.dora_hyouji.push(Hai::Pinzu(2));
+        let ko = tensuu("234567m345p678s55s", 4, &condition);
+        assert_eq!((ko.fu, ko.han, ko.limit), (40, 4, Some(Limit::Mangan)));
+        assert_eq!(ko.shiharai, Shiharai::Ron(8000));
+    }
+
+    #[test]
+    fn tsumo() {
+        let mut condition = AgariCondition::new(Hai::Manzu(4), true);
+        condition.honba = 2;
+        condition.kyoutaku = 1;
+        let tehai = "234567m345p678s55s";
+
+        // Menzen tsumo, pinfu and tanyao of 20 fu.
+        let ko = tensuu(tehai, 4, &condition);
+        assert_eq!((ko.fu, ko.han), (20, 3));
+        assert_eq!(ko.shiharai, Shiharai::TsumoKo { oya: 1500, ko: 900 });
+        assert_eq!(ko.total(), 1000 + 1500 + 900 * 2);
+
+        condition.jikaze = Hai::Jihai(1);
+        let oya = tensuu(tehai, 4, &condition);
+        assert_eq!(oya.shiharai, Shiharai::TsumoOya(1500));
+        assert_eq!(oya.total(), 1000 + 1500 * 3);
+    }
+
+    #[test]
+    fn total_of_3_players() {
+        // Menzen tsumo of 30 fu 1 han.
+        let mut condition = AgariCondition::new(Hai::Pinzu(4), true);
+        let ko = tensuu("999m234p456p678s55s", 3, &condition);
+        assert_eq!((ko.fu, ko.han), (30, 1));
+        assert_eq!(ko.shiharai, Shiharai::TsumoKo { oya: 500, ko: 300 });
+        assert_eq!(ko.total(), 500 + 300);
+        let ko = tensuu("999m234p456p678s55s", 4, &condition);
+        assert_eq!(ko.total(), 500 + 300 * 2);
+
+        condition.jikaze = Hai::Jihai(1);
+        let oya = tensuu("999m234p456p678s55s", 3, &condition);
+        assert_eq!(oya.shiharai, Shiharai::TsumoOya(500));
+        assert_eq!(oya.total(), 500 * 2);
+    }
+
+    #[test]
+    fn dora_of_3_players() {
+        let tehai = Tehai::with_player_number("19m123456p789s11z[4z][4z]".to_string(), 3).unwrap();
+        assert_eq!(tehai.nukidora, 2);
+        assert!(tehai.fuuro.is_empty());
+        assert_eq!(tehai.dora(&[Hai::Manzu(1)]), 1);
+        assert_eq!(tehai.dora(&[Hai::Manzu(9)]), 1);
+        assert_eq!(tehai.dora(&[Hai::Jihai(3)]), 2);
+        assert!(Tehai::with_player_number("123m456p789s11z".to_string(), 3).is_err());
+    }
+}
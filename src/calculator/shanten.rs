@@ -0,0 +1,247 @@
+use super::{Error, Hai, Tehai};
+
+/// Table of suuhai generated by `build.rs`, 4 bytes for each base-5 key of 9 counts.
+static SUUHAI_TABLE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/suuhai_table.bin"));
+
+const MAX_MENTSU: usize = 4;
+
+/// Most number of taatsu and toitsu, indexed by if jantou taken and number of mentsu.
+/// `-1` for impossible.
+type Blocks = [[i8; MAX_MENTSU + 1]; 2];
+
+fn suuhai_blocks(counts: &[u8]) -> Blocks {
+    let key = counts
+        .iter()
+        .rev()
+        .fold(0usize, |key, count| key * 5 + *count as usize);
+    let entry = u32::from_le_bytes([
+        SUUHAI_TABLE[4 * key],
+        SUUHAI_TABLE[4 * key + 1],
+        SUUHAI_TABLE[4 * key + 2],
+        SUUHAI_TABLE[4 * key + 3],
+    ]);
+
+    let mut blocks = [[-1; MAX_MENTSU + 1]; 2];
+    for (jantou, partial_vec) in blocks.iter_mut().enumerate() {
+        for (mentsu, partial) in partial_vec.iter_mut().enumerate() {
+            let value = (entry >> (3 * (jantou * (MAX_MENTSU + 1) + mentsu))) & 7;
+            if value != 7 {
+                *partial = value as i8;
+            }
+        }
+    }
+    blocks
+}
+
+/// Jihai cannot make juntsu or taatsu, so each one is alone.
+fn jihai_blocks(count: u8) -> Blocks {
+    let mut blocks = [[-1; MAX_MENTSU + 1]; 2];
+    blocks[0][0] = 0;
+    if count >= 2 {
+        blocks[0][0] = 1;
+        blocks[1][0] = 0;
+    }
+    if count >= 3 {
+        blocks[0][1] = 0;
+    }
+    blocks
+}
+
+fn merge(lhs: &Blocks, rhs: &Blocks) -> Blocks {
+    let mut blocks = [[-1; MAX_MENTSU + 1]; 2];
+    for lhs_jantou in 0..2 {
+        for rhs_jantou in 0..2 - lhs_jantou {
+            for lhs_mentsu in 0..=MAX_MENTSU {
+                for rhs_mentsu in 0..=MAX_MENTSU - lhs_mentsu {
+                    let lhs_partial = lhs[lhs_jantou][lhs_mentsu];
+                    let rhs_partial = rhs[rhs_jantou][rhs_mentsu];
+                    if lhs_partial < 0 || rhs_partial < 0 {
+                        continue;
+                    }
+                    let slot = &mut blocks[lhs_jantou + rhs_jantou][lhs_mentsu + rhs_mentsu];
+                    *slot = (*slot).max(lhs_partial + rhs_partial);
+                }
+            }
+        }
+    }
+    blocks
+}
+
+/// Shanten of mentsute, same as `Decomposer::shanten`.
+fn mentsute_shanten(counts: &[u8; 34], juntehai_number: usize) -> i32 {
+    let mut blocks = suuhai_blocks(&counts[0..9]);
+    blocks = merge(&blocks, &suuhai_blocks(&counts[9..18]));
+    blocks = merge(&blocks, &suuhai_blocks(&counts[18..27]));
+    for count in &counts[27..34] {
+        blocks = merge(&blocks, &jihai_blocks(*count));
+    }
+
+    // Mentsu and jantou needed, like 4 mentsu and 1 jantou for 13 or 14 hai.
+    let max_mentsu_toitsu_taatsu = (juntehai_number as i32 + 2) / 3;
+    let mut shanten = ((juntehai_number / 3) * 2) as i32;
+    for (jantou, partial_vec) in blocks.iter().enumerate() {
+        for (mentsu, partial) in partial_vec.iter().enumerate() {
+            if *partial < 0 {
+                continue;
+            }
+            let mentsu = mentsu as i32;
+            let taatsu = (*partial as i32).min(max_mentsu_toitsu_taatsu - 1 - mentsu);
+            shanten = shanten.min(
+                ((juntehai_number / 3) * 2) as i32 - 2 * mentsu - jantou as i32 - taatsu,
+            );
+        }
+    }
+    shanten
+}
+
+fn chiitoitsu_shanten(counts: &[u8; 34]) -> i32 {
+    let toitsu = counts.iter().filter(|count| **count >= 2).count() as i32;
+    let ukihai = counts.iter().filter(|count| **count == 1).count() as i32;
+    13 - 2 * toitsu - ukihai.min(7 - toitsu)
+}
+
+fn kokushimusou_shanten(counts: &[u8; 34]) -> i32 {
+    let yaochuupai: Vec<u8> = Hai::yaochuupai_type()
+        .iter()
+        .map(|hai| counts[hai.index()])
+        .collect();
+    let kind = yaochuupai.iter().filter(|count| **count > 0).count() as i32;
+    let toitsu = yaochuupai.iter().any(|count| *count >= 2) as i32;
+    13 - kind - toitsu
+}
+
+/// Shanten of 3*k+2 counts, with chiitoitsu and kokushimusou if no fuuro.
+fn table_shanten(counts: &[u8; 34], juntehai_number: usize, menzen: bool) -> i32 {
+    let mut shanten = mentsute_shanten(counts, juntehai_number);
+    if juntehai_number == 14 && menzen {
+        shanten = shanten
+            .min(chiitoitsu_shanten(counts))
+            .min(kokushimusou_shanten(counts));
+    }
+    shanten
+}
+
+impl Tehai {
+    /// Return the number of shanten of 3*k+1 or 3*k+2 tehai. -1 means agari.
+    ///
+    /// Tehai with no more than 14 juntehai is calculated by the table generated at build
+    /// time, which is much faster than decomposing. Longer one falls back to decomposing.
+    /// 3*k+1 tehai takes the best of drawing every hai, the fifth one excluded.
+    pub fn shanten(&self) -> Result<i32, Error> {
+        let juntehai_number = self.juntehai.len();
+        if juntehai_number.is_multiple_of(3) {
+            return Err(Error::WrongHaiNumber {
+                remainder: 2,
+                found: juntehai_number,
+            });
+        }
+        if juntehai_number > 14 {
+            return self.recursive_shanten();
+        }
+
+        let mut counts = self.counts().normalized();
+
+        let menzen = self.fuuro.is_empty();
+        if juntehai_number % 3 == 2 {
+            return Ok(table_shanten(&counts, juntehai_number, menzen));
+        }
+
+        let mut shanten = None;
+        for hai in Hai::all_type(self.player_number) {
+            if counts[hai.index()] >= 4 {
+                continue;
+            }
+            counts[hai.index()] += 1;
+            let drawn_shanten = table_shanten(&counts, juntehai_number + 1, menzen) + 1;
+            counts[hai.index()] -= 1;
+            shanten = Some(shanten.map_or(drawn_shanten, |cur: i32| cur.min(drawn_shanten)));
+        }
+        shanten.ok_or(Error::Logic("No hai can be drawn."))
+    }
+
+    /// Return the number of shanten of 3*k+2 normalized counts as juntehai, keeping fuuro
+    /// of this tehai. Used to try many juntehai without building tehai each time.
+    pub(super) fn counts_shanten(&self, counts: &[u8; 34]) -> Result<i32, Error> {
+        let juntehai_number = counts.iter().map(|count| *count as usize).sum::<usize>();
+        if juntehai_number <= 14 {
+            return Ok(table_shanten(
+                counts,
+                juntehai_number,
+                self.fuuro.is_empty(),
+            ));
+        }
+
+        let mut tehai = self.clone();
+        tehai.juntehai = counts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, count)| Some(vec![Hai::from_index(index)?; *count as usize]))
+            .flatten()
+            .collect();
+        tehai.recursive_shanten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deal random tehai from a wall of `suit_number` suits, jihai included if 4.
+    fn deal(seed: &mut u64, juntehai_number: usize, suit_number: usize) -> Tehai {
+        let mut wall: Vec<Hai> = Hai::all_type(4)
+            .into_iter()
+            .filter(|hai| hai.index() < 9 * suit_number || suit_number == 4)
+            .flat_map(|hai| [hai; 4])
+            .collect();
+        let mut juntehai = vec![];
+        for _ in 0..juntehai_number {
+            *seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            juntehai.push(wall.swap_remove((*seed >> 33) as usize % wall.len()));
+        }
+        juntehai.sort();
+        Tehai {
+            juntehai,
+            fuuro: vec![],
+            nukidora: 0,
+            player_number: 4,
+        }
+    }
+
+    #[test]
+    fn table_matches_decomposing() {
+        let mut seed = 20190622;
+        for (juntehai_number, times) in [(2, 100), (5, 100), (8, 100), (11, 100), (14, 200)] {
+            for suit_number in [1, 2, 4] {
+                for _ in 0..times {
+                    let tehai = deal(&mut seed, juntehai_number, suit_number);
+                    assert_eq!(
+                        tehai.shanten().unwrap(),
+                        tehai.recursive_shanten().unwrap(),
+                        "{}",
+                        tehai
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn table_matches_decomposing_without_discard() {
+        let mut seed = 20190623;
+        for (juntehai_number, times) in [(1, 10), (4, 10), (7, 10), (10, 10), (13, 10)] {
+            for suit_number in [1, 2, 4] {
+                for _ in 0..times {
+                    let tehai = deal(&mut seed, juntehai_number, suit_number);
+                    assert_eq!(
+                        tehai.shanten().unwrap(),
+                        tehai.recursive_shanten().unwrap(),
+                        "{}",
+                        tehai
+                    );
+                }
+            }
+        }
+    }
+}
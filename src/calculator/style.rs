@@ -0,0 +1,108 @@
+use super::Hai;
+use std::fmt;
+
+/// How hai are printed by `Styled`.
+///
+/// # Variant
+/// * Text: `1m`, and `0m` for red five, same as input.
+/// * Unicode: mahjong tiles like `🀇`, with `r` after red five like `🀋r` as mjai does. Both
+///   can be parsed back.
+/// * Color: text in ANSI colors, red manzu, blue pinzu, green souzu and yellow jihai, with
+///   red five in white on red background.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HaiStyle {
+    #[default]
+    Text,
+    Unicode,
+    Color,
+}
+
+/// Value printed with all hai in a style.
+///
+/// `Display` of such value is the same as `HaiStyle::Text`, and `styled` wraps it for other
+/// styles, like `println!("{}", tehai.styled(HaiStyle::Unicode))`.
+pub trait StyledDisplay {
+    /// Format with every hai in `style`.
+    fn fmt_styled(&self, f: &mut fmt::Formatter<'_>, style: HaiStyle) -> fmt::Result;
+
+    /// Wrap to be printed in `style` by `Display`.
+    fn styled(&self, style: HaiStyle) -> Styled<'_, Self> {
+        Styled { value: self, style }
+    }
+}
+
+/// Wrapper printing a value in a style by `Display`, made by `StyledDisplay::styled`.
+#[derive(Copy, Clone, Debug)]
+pub struct Styled<'a, T: ?Sized> {
+    value: &'a T,
+    style: HaiStyle,
+}
+
+impl<T: StyledDisplay + ?Sized> fmt::Display for Styled<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt_styled(f, self.style)
+    }
+}
+
+impl StyledDisplay for Hai {
+    fn fmt_styled(&self, f: &mut fmt::Formatter<'_>, style: HaiStyle) -> fmt::Result {
+        let (Hai::Manzu(num) | Hai::Pinzu(num) | Hai::Souzu(num) | Hai::Jihai(num)) = self;
+        match style {
+            HaiStyle::Text => write!(f, "{}{}", num, self.type_char()),
+            HaiStyle::Unicode => {
+                write!(f, "{}", self.unicode_char())?;
+                if self.is_aka() {
+                    write!(f, "r")?;
+                }
+                Ok(())
+            }
+            HaiStyle::Color => {
+                let color = match self {
+                    _ if self.is_aka() => "1;97;41",
+                    Hai::Manzu(_) => "31",
+                    Hai::Pinzu(_) => "34",
+                    Hai::Souzu(_) => "32",
+                    Hai::Jihai(_) => "33",
+                };
+                write!(f, "\x1b[{}m{}{}\x1b[0m", color, num, self.type_char())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::Tehai;
+
+    #[test]
+    fn unicode_char_matches_alias() {
+        for hai in Hai::all_type(4) {
+            assert_eq!(Hai::from_alias(hai.unicode_char()), Some(hai));
+        }
+    }
+
+    #[test]
+    fn styled_parsed_back() {
+        for input in ["123406m4440p8s[<111z]", "11z[3<12s][^555+0p](9999s)"] {
+            let tehai = Tehai::new(input.to_string()).unwrap();
+            for style in [HaiStyle::Text, HaiStyle::Unicode] {
+                let styled = tehai.styled(style).to_string();
+                assert_eq!(Tehai::new(styled).unwrap(), tehai, "{}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn styled_hai() {
+        let styled = |hai: Hai, style| hai.styled(style).to_string();
+        assert_eq!(styled(Hai::Pinzu(0), HaiStyle::Text), "0p");
+        assert_eq!(styled(Hai::Pinzu(0), HaiStyle::Unicode), "🀝r");
+        assert_eq!(styled(Hai::Jihai(7), HaiStyle::Unicode), "🀄");
+        assert_eq!(styled(Hai::Souzu(3), HaiStyle::Color), "\x1b[32m3s\x1b[0m");
+        assert_eq!(
+            styled(Hai::Manzu(0), HaiStyle::Color),
+            "\x1b[1;97;41m0m\x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use super::{Error, Hai, Tehai};
+
+/// Hai can be seen on the table besides tehai.
+///
+/// # Japanese
+/// * Taku: 卓
+/// * dora_hyouji: ドラ表示牌
+/// * uradora_hyouji: 裏ドラ表示牌
+/// * kawa: 河
+/// * tacha: 他家
+///
+/// # Member
+/// * dora_hyouji: indicators of dora.
+/// * uradora_hyouji: indicators of uradora. Usually only seen after winning with riichi,
+///   but counted as visible if provided.
+/// * kawa: sutehai of the player, deciding furiten.
+/// * tacha_kawa: sutehai of other players and hai of their fuuro, or anything else visible.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Taku {
+    pub dora_hyouji: Vec<Hai>,
+    pub uradora_hyouji: Vec<Hai>,
+    pub kawa: Vec<Hai>,
+    pub tacha_kawa: Vec<Hai>,
+}
+
+impl Taku {
+    /// Parse hai for taku, like `1z3p`, in the same way as tehai but `[]` and `()` not
+    /// allowed, so no hai of fuuro or nukidora is dropped silently.
+    pub fn parse_hai_vec(string: &str, player_number: u8) -> Result<Vec<Hai>, Error> {
+        if let Some(index) = string
+            .chars()
+            .position(|chr| matches!(chr, '[' | ']' | '(' | ')'))
+        {
+            return Err(Error::UnexpectedBracket { index });
+        }
+        Ok(Tehai::with_player_number(string.to_string(), player_number)?.juntehai)
+    }
+
+    /// Return all visible hai on the table, which cannot be drawn any more.
+    pub fn visible_hai(&self) -> Vec<Hai> {
+        let mut visible_hai = self.dora_hyouji.clone();
+        visible_hai.extend(&self.uradora_hyouji);
+        visible_hai.extend(&self.kawa);
+        visible_hai.extend(&self.tacha_kawa);
+        visible_hai
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::Tehai;
+
+    #[test]
+    fn parse_hai_vec() {
+        assert_eq!(
+            Taku::parse_hai_vec("1z 30p", 4),
+            Ok(vec![Hai::Pinzu(3), Hai::Pinzu(0), Hai::Jihai(1)])
+        );
+        assert_eq!(
+            Taku::parse_hai_vec("(1111z)", 4),
+            Err(Error::UnexpectedBracket { index: 0 })
+        );
+        assert_eq!(
+            Taku::parse_hai_vec("1z1111z)", 4),
+            Err(Error::UnexpectedBracket { index: 7 })
+        );
+        assert_eq!(
+            Taku::parse_hai_vec("9m[4z]", 3),
+            Err(Error::UnexpectedBracket { index: 2 })
+        );
+    }
+
+    #[test]
+    fn dora_hyouji_not_in_machihai() {
+        let tehai = Tehai::new("123m456m789m11z23s5z".to_string()).unwrap();
+        let taku = Taku {
+            dora_hyouji: vec![Hai::Souzu(4)],
+            uradora_hyouji: vec![Hai::Souzu(1), Hai::Souzu(1)],
+            ..Default::default()
+        };
+        let (shanten, conditions) = tehai.analyze(&taku).unwrap();
+        assert_eq!(shanten, 0);
+        let condition = conditions
+            .iter()
+            .find(|condition| condition.sutehai == Hai::Jihai(5))
+            .unwrap();
+        assert_eq!(condition.machihai.get(&Hai::Souzu(1)), Some(&2));
+        assert_eq!(condition.machihai.get(&Hai::Souzu(4)), Some(&3));
+
+        let (_, conditions) = tehai.analyze(&Taku::default()).unwrap();
+        assert_eq!(conditions[0].sutehai, Hai::Jihai(5));
+        assert_eq!(conditions[0].nokori(), 8);
+    }
+
+    #[test]
+    fn too_many_hai_with_taku() {
+        let tehai = Tehai::new("123m456m789m11z23s5z".to_string()).unwrap();
+        let taku = Taku {
+            dora_hyouji: vec![Hai::Jihai(1), Hai::Jihai(1), Hai::Jihai(1)],
+            ..Default::default()
+        };
+        assert!(tehai.analyze(&taku).is_err());
+        let taku = Taku {
+            dora_hyouji: vec![Hai::Pinzu(0), Hai::Pinzu(0)],
+            ..Default::default()
+        };
+        assert!(tehai.analyze(&taku).is_err());
+    }
+
+    #[test]
+    fn furiten_of_each_sutehai() {
+        let condition = |tehai: &str, kawa: Vec<Hai>, sutehai: Hai| {
+            let tehai = Tehai::new(tehai.to_string()).unwrap();
+            let taku = Taku {
+                kawa,
+                ..Default::default()
+            };
+            let (shanten, conditions) = tehai.analyze(&taku).unwrap();
+            assert_eq!(shanten, 0);
+            conditions
+                .into_iter()
+                .find(|condition| condition.sutehai == sutehai)
+                .unwrap()
+        };
+
+        // Discarding 1z waits for 2s and 5s, others wait for 1z.
+        let tehai = "123m456m789m1z2345s";
+        assert!(!condition(tehai, vec![], Hai::Jihai(1)).furiten);
+        assert!(condition(tehai, vec![Hai::Souzu(2)], Hai::Jihai(1)).furiten);
+        assert!(!condition(tehai, vec![Hai::Souzu(2)], Hai::Souzu(2)).furiten);
+        assert!(!condition(tehai, vec![Hai::Souzu(2)], Hai::Souzu(5)).furiten);
+        assert!(condition(tehai, vec![Hai::Jihai(1)], Hai::Souzu(5)).furiten);
+
+        // Red five in kawa works as normal five.
+        assert!(condition(tehai, vec![Hai::Souzu(0)], Hai::Jihai(1)).furiten);
+
+        // Kawa is visible, so not counted in machihai.
+        let furiten = condition(tehai, vec![Hai::Souzu(2)], Hai::Jihai(1));
+        assert_eq!(furiten.machihai.get(&Hai::Souzu(2)), Some(&2));
+    }
+}
@@ -1,5 +1,8 @@
-use super::{ Hai, Mentsu, Taatsu, Toitsu, Ukihai};
-use std::collections::{BTreeMap, HashMap, HashSet};
+use super::{
+    Error, Fuuro, FuuroKind, Hai, HaiCounts, HaiStyle, Mentsu, StyledDisplay, Taatsu, Tacha,
+    Taku, Toitsu, Ukihai,
+};
+use std::collections::{BTreeMap, HashSet};
 
 /// hai on hand.
 ///
@@ -7,14 +10,19 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 /// * Tehai: 手牌
 /// * juntehai: 純手牌
 /// * fuuro: 副露
+/// * nukidora: 抜きドラ
 ///
 /// # Member
 /// * juntehai: Vec of hai which not formed mentsu.
-/// * fuuro: Mentsu which already formed.
+/// * fuuro: Mentsu which already formed, by calling or by ankan.
+/// * nukidora: Number of `4z` set aside as dora, only on 3-players mode.
+/// * player_number: Number of players, 4 or 3.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tehai {
     pub juntehai: Vec<Hai>,
-    pub fuuro: Vec<Mentsu>,
+    pub fuuro: Vec<Fuuro>,
+    pub nukidora: u8,
+    pub player_number: u8,
 }
 
 /// Form of tehai when winning.
@@ -37,9 +45,9 @@ pub enum Hourakei {
 ///
 /// # Member
 /// * valid_ukihai_vec: Ukihai that can provide shanten. such as `3p`, `5p`, `6p` and `8p` in
-/// `11224477m356778p`, or any yaochuupai in kokushimusou type.
+///   `11224477m356778p`, or any yaochuupai in kokushimusou type.
 /// * invalid_ukihai_vec: Ukihai that cannot provide shanten, absolutely useless. such as `1m`
-/// in `111224477m34577p`, or any non-yaochuupai in kokushimusou type.
+///   in `111224477m34577p`, or any non-yaochuupai in kokushimusou type.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 struct Decomposer {
     mentsu_vec: Vec<Mentsu>,
@@ -60,7 +68,8 @@ struct Decomposer {
 /// # Member
 /// * sutehai: which ukihai will be discarded.
 /// * machihai: hai waiting for.
-/// * furiten: if machihai included prevenient sutehai.
+/// * furiten: if tenpai after discarding sutehai, but machihai included sutehai or any hai
+///   in kawa.
 #[derive(Clone, Debug)]
 pub struct MachiCondition {
     pub sutehai: Hai,
@@ -87,32 +96,50 @@ impl Tehai {
     /// * shorter: `123445m4445p8s[111z]`
     /// * with spaces: `123445m 4445p 8s [111z]`
     /// * chaos: `45p 8s14 4m[11 1z]2 5m44p 3m`
-    pub fn new(string: String) -> Result<Self, String> {
+    /// * with red five: `123406m4440p8s[111z]`
+    /// * with called hai: `123445m4445p8s[1^11z]`, see `Fuuro` for all kinds.
+    /// * with ankan: `123445m4p8s[1^11z](5555p)`
+    /// * with aliases of `Hai`: `123445m4445p8s[EEE]`, `1m2m3m4m4m5mr`, `🀇🀈🀉🀀🀀`
+    ///
+    /// Red five can also be written as `5mr` in mjai style, where `r` must follow a single
+    /// five right away, so `55mr` is an error rather than guessing which one is red.
+    pub fn new(string: String) -> Result<Self, Error> {
+        Self::with_player_number(string, 4)
+    }
+
+    /// Create tehai from string for 3 or 4 players.
+    ///
+    /// On 3-players mode, 2\~8m and 0m are invalid, and each `[4z]` is a nukidora,
+    /// like `19m123456p789s11z[4z][4z]`.
+    pub fn with_player_number(string: String, player_number: u8) -> Result<Self, Error> {
         fn handle_char_stash(
+            player_number: u8,
             hai_type: char,
             hai_type_char_index: usize,
-            char_stash: &mut Vec<char>,
+            char_stash: &mut Vec<(usize, char)>,
             output: &mut Vec<Hai>,
-        ) -> Result<(), String> {
-            if char_stash.len() == 0 {
-                Err(format!(
-                    "Unused type character '{}' at index {}.",
-                    hai_type, hai_type_char_index
-                ))
+            hai_index: &mut Vec<(usize, Hai)>,
+        ) -> Result<(), Error> {
+            if char_stash.is_empty() {
+                Err(Error::UnusedTypeCharacter {
+                    chr: hai_type,
+                    index: hai_type_char_index,
+                })
             } else {
-                for hai in char_stash.iter() {
+                for &(index, hai) in char_stash.iter() {
                     let hai = match hai_type {
-                        'm' => Hai::Manzu(*hai as u8 - 48),
-                        'p' => Hai::Pinzu(*hai as u8 - 48),
-                        's' => Hai::Souzu(*hai as u8 - 48),
-                        'z' => Hai::Jihai(*hai as u8 - 48),
+                        'm' => Hai::Manzu(hai as u8 - 48),
+                        'p' => Hai::Pinzu(hai as u8 - 48),
+                        's' => Hai::Souzu(hai as u8 - 48),
+                        'z' => Hai::Jihai(hai as u8 - 48),
                         _ => Hai::Manzu(0), // Never reach here.
                     };
-                    if hai.is_valid() {
+                    if hai.is_valid(player_number) {
                         output.push(hai);
+                        hai_index.push((index, hai));
                     } else {
                         char_stash.clear();
-                        return Err(format!("'{}' is invalid hai.", hai.to_string()));
+                        return Err(Error::InvalidHai { hai, index });
                     }
                 }
                 char_stash.clear();
@@ -120,113 +147,294 @@ impl Tehai {
             }
         }
 
+        /// Make a fuuro of hai in `[]` or `()`, with marks as position of hai and character.
         fn handle_hai_in_mentsu_stash(
+            player_number: u8,
             char_index: usize,
+            ankan: bool,
             hai_in_mentsu_stash: &mut Vec<Hai>,
-            output: &mut Vec<Mentsu>,
-        ) -> Result<(), String> {
-            let mentsu = Mentsu::new(hai_in_mentsu_stash).ok_or(format!(
-                "Not a valid meld on '[]' before index {}.",
-                char_index
-            ))?;
-
-            output.push(mentsu);
-            hai_in_mentsu_stash.clear();
+            mark_stash: &mut Vec<(usize, char)>,
+            output: &mut Vec<Fuuro>,
+            nukidora: &mut u8,
+        ) -> Result<(), Error> {
+            let hai_vec = std::mem::take(hai_in_mentsu_stash);
+            let mark_vec = std::mem::take(mark_stash);
+            let invalid = Error::InvalidMentsu { index: char_index };
+
+            if player_number == 3 && !ankan && mark_vec.is_empty() && hai_vec == [Hai::Jihai(4)] {
+                *nukidora += 1;
+                return Ok(());
+            }
+
+            let mentsu = Mentsu::new(&hai_vec).ok_or(invalid.clone())?;
+            if ankan {
+                return match (mentsu, &mark_vec[..]) {
+                    (Mentsu::Kantsu(_), []) => {
+                        output.push(Fuuro::ankan(mentsu));
+                        Ok(())
+                    }
+                    _ => Err(invalid),
+                };
+            }
+
+            let mut fuuro = Fuuro::new(mentsu);
+            let mut called = None;
+            let mut added = None;
+            for (position, mark) in mark_vec {
+                let hai = *hai_vec.get(position).ok_or(invalid.clone())?;
+                let from = match mark {
+                    '<' => Tacha::Kamicha,
+                    '^' => Tacha::Toimen,
+                    '>' => Tacha::Shimocha,
+                    _ => {
+                        if added.replace(position).is_some() {
+                            return Err(invalid);
+                        }
+                        continue;
+                    }
+                };
+                if fuuro.from.replace(from).is_some() {
+                    return Err(invalid);
+                }
+                fuuro.called = Some(hai);
+                called = Some(position);
+                if fuuro.kind == FuuroKind::Chii && from != Tacha::Kamicha {
+                    return Err(invalid);
+                }
+            }
+            if added.is_some() {
+                if fuuro.kind != FuuroKind::Daiminkan || called.is_none() || called == added {
+                    return Err(invalid);
+                }
+                fuuro.kind = FuuroKind::Kakan;
+            }
+
+            output.push(fuuro);
             Ok(())
         }
 
         let mut juntehai = vec![];
         let mut fuuro = vec![];
-        let mut char_stash: Vec<char> = vec![];
+        let mut nukidora = 0;
+        let mut char_stash: Vec<(usize, char)> = vec![];
         let mut hai_in_mentsu_stash: Vec<Hai> = vec![];
-        let mut in_mentsu = false;
+        let mut mark_stash: Vec<(usize, char)> = vec![];
+        // All hai in input order with index, to find where too many hai are.
+        let mut hai_index: Vec<(usize, Hai)> = vec![];
+        // `[` or `(` if in mentsu.
+        let mut bracket = None;
+        let mut mentsu_start_index = 0;
+        // Index right after the last hai written alone, like `5m` or `E`, to be made red by `r`.
+        let mut single_hai_end = None;
 
         for (index, chr) in string.chars().enumerate() {
             match chr {
                 'm' | 'p' | 's' | 'z' => {
-                    if in_mentsu {
+                    if char_stash.len() == 1 {
+                        single_hai_end = Some(index + 1);
+                    }
+                    if bracket.is_some() {
                         handle_char_stash(
+                            player_number,
                             chr,
                             index,
                             &mut char_stash,
                             &mut hai_in_mentsu_stash,
+                            &mut hai_index,
                         )?;
                     } else {
                         handle_char_stash(
+                            player_number,
                             chr,
                             index,
                             &mut char_stash,
                             &mut juntehai,
+                            &mut hai_index,
                         )?;
                     }
                 }
-                '1'..='9' => char_stash.push(chr),
-                '[' => {
-                    if in_mentsu {
-                        return Err(format!("Second '[' found at index {}.", index));
+                '0'..='9' => char_stash.push((index, chr)),
+                '[' | '(' => {
+                    if bracket.is_some() {
+                        return Err(Error::NestedBracket { index });
                     }
-                    if char_stash.len() > 0 {
-                        return Err(format!(
-                            "Need 'm' 'p' 's' 'z' but find '[' at index {}.",
-                            index
-                        ));
+                    if !char_stash.is_empty() {
+                        return Err(Error::MissingTypeCharacter {
+                            chr: Some(chr),
+                            index,
+                        });
                     };
-                    in_mentsu = true;
+                    bracket = Some(chr);
+                    mentsu_start_index = index;
                 }
-                ']' => {
-                    if !in_mentsu {
-                        return Err(format!("Unmatched ']' found at index {}.", index));
-                    }
-                    if char_stash.len() > 0 {
-                        return Err(format!(
-                            "Need 'm' 'p' 's' 'z' but find ']' at index {}.",
-                            index
-                        ));
+                ']' | ')' => {
+                    let ankan = match (bracket, chr) {
+                        (Some('['), ']') => false,
+                        (Some('('), ')') => true,
+                        _ => return Err(Error::UnmatchedBracket { index }),
+                    };
+                    if !char_stash.is_empty() {
+                        return Err(Error::MissingTypeCharacter {
+                            chr: Some(chr),
+                            index,
+                        });
                     };
                     handle_hai_in_mentsu_stash(
+                        player_number,
                         index,
+                        ankan,
                         &mut hai_in_mentsu_stash,
+                        &mut mark_stash,
                         &mut fuuro,
+                        &mut nukidora,
                     )?;
-                    in_mentsu = false;
+                    bracket = None;
                 }
-                // Ignore all spaces.
-                ' ' => (),
+                // Marks of the called hai and the added hai of kakan, only in `[]`.
+                '<' | '^' | '>' | '+' if bracket == Some('[') => {
+                    mark_stash.push((hai_in_mentsu_stash.len() + char_stash.len(), chr));
+                }
+                'r' => {
+                    let output = if bracket.is_some() {
+                        &mut hai_in_mentsu_stash
+                    } else {
+                        &mut juntehai
+                    };
+                    match output.last_mut() {
+                        Some(hai @ (Hai::Manzu(5) | Hai::Pinzu(5) | Hai::Souzu(5)))
+                            if single_hai_end == Some(index) =>
+                        {
+                            *hai = match hai {
+                                Hai::Manzu(_) => Hai::Manzu(0),
+                                Hai::Pinzu(_) => Hai::Pinzu(0),
+                                _ => Hai::Souzu(0),
+                            };
+                            if let Some((_, last)) = hai_index.last_mut() {
+                                *last = *hai;
+                            }
+                        }
+                        _ => return Err(Error::UnusedAkaCharacter { index }),
+                    }
+                }
+                // Ignore all spaces, and variation selectors after tiles like `🀄️`.
+                ' ' | '\u{FE0E}' | '\u{FE0F}' => (),
                 _ => {
-                    return Err(format!("Unknown character '{}' at index {}.", chr, index));
+                    let hai = Hai::from_alias(chr).ok_or(Error::UnknownCharacter { chr, index })?;
+                    if !char_stash.is_empty() {
+                        return Err(Error::MissingTypeCharacter {
+                            chr: Some(chr),
+                            index,
+                        });
+                    }
+                    if !hai.is_valid(player_number) {
+                        return Err(Error::InvalidHai { hai, index });
+                    }
+                    if bracket.is_some() {
+                        hai_in_mentsu_stash.push(hai);
+                    } else {
+                        juntehai.push(hai);
+                    }
+                    hai_index.push((index, hai));
+                    single_hai_end = Some(index + 1);
                 }
             }
         }
 
-        if char_stash.len() > 0 {
-            return Err(format!(
-                "No type specified for '{:?}' at the end of input string.",
-                char_stash
-            ));
+        if !char_stash.is_empty() {
+            return Err(Error::MissingTypeCharacter {
+                chr: None,
+                index: char_stash[0].0,
+            });
+        }
+        if bracket.is_some() {
+            return Err(Error::UnclosedBracket {
+                index: mentsu_start_index,
+            });
         }
 
         juntehai.sort();
-        let tehai = Self { juntehai, fuuro };
+        let tehai = Self {
+            juntehai,
+            fuuro,
+            nukidora,
+            player_number,
+        };
+
+        Self::check_hai_number(hai_index.into_iter().map(|(index, hai)| (Some(index), hai)))?;
+        Ok(tehai)
+    }
 
-        match tehai.check_hai_number() {
-            Ok(_) => Ok(tehai),
-            Err(hai) => Err(format!("Fifth {} found.", hai.to_string())),
+    /// Return all hai of tehai, including juntehai, fuuro and nukidora.
+    pub fn all_hai(&self) -> Vec<Hai> {
+        let mut all_hai = self.juntehai.clone();
+        for fuuro in &self.fuuro {
+            all_hai.extend(fuuro.hai_vec());
         }
+        for _ in 0..self.nukidora {
+            all_hai.push(Hai::Jihai(4));
+        }
+        all_hai
+    }
+
+    /// Return the number of red five in tehai.
+    pub fn aka(&self) -> u8 {
+        let mut aka = self.juntehai.iter().filter(|hai| hai.is_aka()).count();
+        for fuuro in &self.fuuro {
+            aka += fuuro.hai_vec().iter().filter(|hai| hai.is_aka()).count();
+        }
+        aka as u8
+    }
+
+    /// Return the tehai with all red five replaced by normal five.
+    pub fn normalize(&self) -> Tehai {
+        let mut juntehai: Vec<Hai> = self.juntehai.iter().map(|hai| hai.normalize()).collect();
+        juntehai.sort();
+        Tehai {
+            juntehai,
+            fuuro: self.fuuro.iter().map(|fuuro| fuuro.normalize()).collect(),
+            nukidora: self.nukidora,
+            player_number: self.player_number,
+        }
+    }
+
+    /// Return compact notation like `123m456p[1^11z]`, which is the canonical form of input.
+    ///
+    /// `Tehai::with_player_number` parses it back to the same tehai, as long as juntehai is
+    /// sorted and no fuuro has `called` without `from`, which is always true for parsed tehai.
+    pub fn to_compact_string(&self) -> String {
+        let mut juntehai: Vec<(&str, Hai)> = self.juntehai.iter().map(|hai| ("", *hai)).collect();
+        juntehai.sort_by_key(|(_, hai)| *hai);
+
+        let mut output = Hai::compact_string(&juntehai);
+        for fuuro in &self.fuuro {
+            output += &fuuro.to_compact_string();
+        }
+        for _ in 0..self.nukidora {
+            output += "[4z]";
+        }
+        output
     }
 
     /// Analyze conditions of sutehai and machihai.
     ///
+    /// Red five works as normal five here, and will be sutehai only if no normal five left.
+    /// Hai visible on taku, like dora indicators and kawa, are not counted in machihai.
+    ///
     /// # Return
     /// * i32: the number of shanten.
     /// * Vec<Condition>: all conditions of different sutehai.
     pub fn analyze(
         &self,
-    ) -> Result<(i32, Vec<MachiCondition>), String> {
-        let (shanten, decomposers) = self.decompose()?;
+        taku: &Taku,
+    ) -> Result<(i32, Vec<MachiCondition>), Error> {
+        self.check_taku(taku)?;
+
+        let tehai = self.normalize();
+        let (shanten, decomposers) = tehai.decompose()?;
         let mut conditions_vec = vec![];
 
         if let i32::MIN..=-2 = shanten {
-            return Err("Logic Error: Shanten is less than -1.".to_string());
+            return Err(Error::Logic("Shanten is less than -1."));
         }
 
         // Tenpai
@@ -240,20 +448,25 @@ impl Tehai {
                 sutehai_set.insert(ukihai.0);
             }
             // Only chiitoitsu type can discard valid tiles but not ukihai.
-            if decomposer.hourakei == Hourakei::Chiitoitsu {
-                if decomposer.invalid_ukihai_vec.len() == 0 {
-                    for Ukihai(sutehai) in &decomposer.valid_ukihai_vec {
-                        sutehai_set.insert(*sutehai);
-                    }
+            if decomposer.hourakei == Hourakei::Chiitoitsu
+                && decomposer.invalid_ukihai_vec.is_empty()
+            {
+                for Ukihai(sutehai) in &decomposer.valid_ukihai_vec {
+                    sutehai_set.insert(*sutehai);
                 }
             }
         }
         for sutehai in sutehai_set {
             let mut condition = MachiCondition::new(sutehai);
             for decomposer in &decomposers {
-                condition.handle(decomposer, self.juntehai.len())?;
+                condition.handle(decomposer, tehai.juntehai.len())?;
+            }
+            condition.finally(&tehai, taku, shanten);
+            if !self.juntehai.contains(&sutehai) {
+                if let Some(aka) = self.juntehai.iter().find(|hai| hai.normalize() == sutehai) {
+                    condition.sutehai = *aka;
+                }
             }
-            condition.finally(self);
             conditions_vec.push(condition);
         }
 
@@ -269,19 +482,100 @@ impl Tehai {
         Ok((shanten, conditions_vec))
     }
 
+    /// Return all machihai of 3*k+1 tehai, empty if not tenpai.
+    ///
+    /// `analyze` gives -1 only for complete 3*k+2 tehai, so every hai is drawn and
+    /// decomposed. Red five is returned as normal five.
+    pub fn machihai(&self) -> Result<Vec<Hai>, Error> {
+        self.machihai_by(|drawn| Ok(drawn.decompose()?.0))
+    }
+
+    /// Return all machihai of 3*k+1 tehai by `shanten` of the tehai drawing each hai,
+    /// except the one tehai already has all 4 of.
+    ///
+    /// Shared by `machihai` decomposing tehai and `game::Player` using shanten table,
+    /// which is much faster for every action of a game.
+    pub(crate) fn machihai_by<F: Fn(&Tehai) -> Result<i32, Error>>(
+        &self,
+        shanten: F,
+    ) -> Result<Vec<Hai>, Error> {
+        if self.juntehai.len() % 3 != 1 {
+            return Err(Error::WrongHaiNumber {
+                remainder: 1,
+                found: self.juntehai.len(),
+            });
+        }
+
+        let tehai = self.normalize();
+        let all_hai = tehai.all_hai();
+        let mut machihai = vec![];
+        for hai in Hai::all_type(self.player_number) {
+            if all_hai.iter().filter(|cur| **cur == hai).count() >= 4 {
+                continue;
+            }
+            let mut drawn = tehai.clone();
+            drawn.juntehai.push(hai);
+            drawn.juntehai.sort();
+            if shanten(&drawn)? == -1 {
+                machihai.push(hai);
+            }
+        }
+        Ok(machihai)
+    }
+
+    /// Return if 3*k+1 tehai is tenpai, waiting for any hai not all in itself.
+    pub fn is_tenpai(&self) -> Result<bool, Error> {
+        Ok(!self.machihai()?.is_empty())
+    }
+
+    /// Return the number of shanten by decomposing. 3*k+1 tehai is calculated by drawing
+    /// every hai.
+    pub(super) fn recursive_shanten(&self) -> Result<i32, Error> {
+        let tehai = self.normalize();
+        if tehai.juntehai.len() % 3 == 2 {
+            return Ok(tehai.decompose()?.0);
+        }
+
+        let mut shanten = None;
+        for hai in Hai::all_type(self.player_number) {
+            if tehai.juntehai.iter().filter(|cur| **cur == hai).count() >= 4 {
+                continue;
+            }
+            let mut drawn = tehai.clone();
+            drawn.juntehai.push(hai);
+            drawn.juntehai.sort();
+            let drawn_shanten = drawn.decompose()?.0 + 1;
+            shanten = Some(shanten.map_or(drawn_shanten, |cur: i32| cur.min(drawn_shanten)));
+        }
+        shanten.ok_or(Error::Logic("No hai can be drawn."))
+    }
+
+    /// Check that hai on taku are valid, and not too many with tehai.
+    pub(super) fn check_taku(&self, taku: &Taku) -> Result<(), Error> {
+        let visible_hai = taku.visible_hai();
+        if let Some(hai) = visible_hai
+            .iter()
+            .find(|hai| !hai.is_valid(self.player_number))
+        {
+            return Err(Error::InvalidTakuHai(*hai));
+        }
+        let mut all_hai = self.all_hai();
+        all_hai.extend(&visible_hai);
+        Self::check_hai_number(all_hai.into_iter().map(|hai| (None, hai)))
+    }
+
     /// Decompose self to a vec of Decomposer.
     ///
     /// # Return
     /// * The `i32` data is the minimum shanten.
     /// * The `HashSet<Decomposer>` data is all decomposers that thier shanten are minimum one.
-    fn decompose(&self) -> Result<(i32, HashSet<Decomposer>), String> {
+    fn decompose(&self) -> Result<(i32, HashSet<Decomposer>), Error> {
         // Only work for 3*k+2 juntehai.
         if self.juntehai.len() % 3 != 2 {
-            return Err(format!(
-                "The number of hai on hand must be 3*k+2, \
-                such as 8, 11, 14, even 17, but {} provided.",
-                self.juntehai.len()
-            ));
+            return Err(Error::WrongHaiNumber {
+                remainder: 2,
+                found: self.juntehai.len(),
+            });
         }
 
         let mut min_shanten = ((self.juntehai.len() / 3) * 2) as i32;
@@ -306,7 +600,7 @@ impl Tehai {
         }
 
         // Analyze Chiitoitsu and Kokushimusou.
-        if self.juntehai.len() != 14 || self.fuuro.len() != 0 {
+        if self.juntehai.len() != 14 || !self.fuuro.is_empty() {
             return Ok((min_shanten, min_shanten_decomposers));
         }
 
@@ -323,20 +617,20 @@ impl Tehai {
                     if cur == last_hai {
                         if !last_hai_used {
                             last_hai_used = true;
-                            decomposer.toitsu_vec.push(Toitsu { 0: *cur });
+                            decomposer.toitsu_vec.push(Toitsu(*cur));
                         } else {
-                            decomposer.invalid_ukihai_vec.push(Ukihai { 0: *cur });
+                            decomposer.invalid_ukihai_vec.push(Ukihai(*cur));
                         }
                     } else {
                         if !last_hai_used {
-                            decomposer.valid_ukihai_vec.push(Ukihai { 0: *last_hai });
+                            decomposer.valid_ukihai_vec.push(Ukihai(*last_hai));
                         }
                         last_hai = cur;
                         last_hai_used = false;
                     }
                 } else {
                     if !last_hai_used {
-                        decomposer.valid_ukihai_vec.push(Ukihai { 0: *last_hai });
+                        decomposer.valid_ukihai_vec.push(Ukihai(*last_hai));
                     }
                     break;
                 }
@@ -357,22 +651,22 @@ impl Tehai {
         let mut yaochuupai_value = yaochuupai_iter.next();
         let mut juntehai_value = juntehai_iter.next();
 
-        while yaochuupai_value != None && juntehai_value != None {
+        while yaochuupai_value.is_some() && juntehai_value.is_some() {
             if let (Some(lhs), Some(rhs)) = (yaochuupai_value, juntehai_value) {
                 if lhs < rhs {
                     yaochuupai_value = yaochuupai_iter.next();
                     yaochuupai_iter_changed = true;
                 } else if lhs > rhs {
-                    decomposer.invalid_ukihai_vec.push(Ukihai { 0: *rhs });
+                    decomposer.invalid_ukihai_vec.push(Ukihai(*rhs));
                     juntehai_value = juntehai_iter.next();
                 } else if lhs == rhs {
                     if yaochuupai_iter_changed {
-                        decomposer.valid_ukihai_vec.push(Ukihai { 0: *rhs });
+                        decomposer.valid_ukihai_vec.push(Ukihai(*rhs));
                     } else if !toitsu_included {
                         toitsu_included = true;
-                        decomposer.valid_ukihai_vec.push(Ukihai { 0: *rhs });
+                        decomposer.valid_ukihai_vec.push(Ukihai(*rhs));
                     } else {
-                        decomposer.invalid_ukihai_vec.push(Ukihai { 0: *rhs });
+                        decomposer.invalid_ukihai_vec.push(Ukihai(*rhs));
                     }
                     yaochuupai_iter_changed = false;
                     juntehai_value = juntehai_iter.next();
@@ -384,57 +678,36 @@ impl Tehai {
         Ok((min_shanten, min_shanten_decomposers))
     }
 
-    fn check_hai_number(&self) -> Result<(), Hai> {
-        let mut tehai_map: HashMap<Hai, u8> = HashMap::new();
-
-        let mut check_count = |hai| -> bool {
-            if tehai_map.contains_key(hai) {
-                let count = tehai_map[hai] + 1;
-                if count > 4 {
-                    return false;
-                }
-                tehai_map.insert(*hai, count);
-            } else {
-                tehai_map.insert(*hai, 1);
-            }
-            return true;
-        };
-
-        for hai in self.juntehai.iter() {
-            if !check_count(hai) {
-                return Err(*hai);
+    /// Check that no more than 4 same hai and no more than 1 red five for each suit.
+    ///
+    /// Return the hai over limit, how many found and its index in input string if known.
+    fn check_hai_number<T>(iter: T) -> Result<(), Error>
+    where
+        T: Iterator<Item = (Option<usize>, Hai)>,
+    {
+        let mut counts = HaiCounts::new();
+
+        for (index, hai) in iter {
+            counts.add(&hai);
+            if counts.count_normalized(&hai) > 4 {
+                return Err(Error::TooManyHai {
+                    hai,
+                    number: counts.count_normalized(&hai),
+                    index,
+                });
             }
-        }
-        for mentsu in self.fuuro.iter() {
-            match mentsu {
-                Mentsu::Juntsu(a, b, c) => {
-                    for hai in vec![a, b, c] {
-                        if !check_count(hai) {
-                            return Err(*hai);
-                        }
-                    }
-                }
-                Mentsu::Koutsu(hai) => {
-                    for _ in 0..3 {
-                        if !check_count(hai) {
-                            return Err(*hai);
-                        }
-                    }
-                }
-                Mentsu::Kantsu(hai) => {
-                    for _ in 0..4 {
-                        if !check_count(hai) {
-                            return Err(*hai);
-                        }
-                    }
-                }
+            if hai.is_aka() && counts.count(&hai) > 1 {
+                return Err(Error::TooManyHai {
+                    hai,
+                    number: counts.count(&hai),
+                    index,
+                });
             }
         }
 
         Ok(())
     }
 
-
     /// http://choco.properties/2019/06/22/%E6%97%A5%E9%BA%BB%E6%8A%98%E8%85%BE%E7%AC%94%E8%AE%B0-02-%E5%90%91%E5%90%AC%E6%95%B0%E7%9A%84%E5%88%A4%E6%96%AD/
     fn split(
         &self,
@@ -448,7 +721,7 @@ impl Tehai {
             ukihai: Hai,
         ) {
             let mut tehai = tehai.clone();
-            decomposer.invalid_ukihai_vec.push(Ukihai { 0: ukihai });
+            decomposer.invalid_ukihai_vec.push(Ukihai(ukihai));
             remove_once(&mut tehai.juntehai, &ukihai);
             tehai.split(decomposers_vec, decomposer);
         }
@@ -461,7 +734,7 @@ impl Tehai {
             rhs: Hai,
         ) {
             let mut tehai = tehai.clone();
-            decomposer.taatsu_vec.push(Taatsu { 0: lhs, 1: rhs });
+            decomposer.taatsu_vec.push(Taatsu(lhs, rhs));
             remove_once(&mut tehai.juntehai, &lhs);
             remove_once(&mut tehai.juntehai, &rhs);
             tehai.split(decomposers_vec, decomposer);
@@ -474,7 +747,7 @@ impl Tehai {
             toitsu: Hai,
         ) {
             let mut tehai = tehai.clone();
-            decomposer.toitsu_vec.push(Toitsu { 0: toitsu });
+            decomposer.toitsu_vec.push(Toitsu(toitsu));
             remove_once(&mut tehai.juntehai, &toitsu);
             remove_once(&mut tehai.juntehai, &toitsu);
             tehai.split(decomposers_vec, decomposer);
@@ -513,9 +786,7 @@ impl Tehai {
         }
 
         if self.juntehai.len() == 1 {
-            decomposer.invalid_ukihai_vec.push(Ukihai {
-                0: self.juntehai[0],
-            });
+            decomposer.invalid_ukihai_vec.push(Ukihai(self.juntehai[0]));
         }
 
         if self.juntehai.len() <= 1 {
@@ -548,16 +819,16 @@ impl Tehai {
         }
 
         if !matches!(current, Hai::Jihai(_)) {
-            let current_plus_one = current.next(false);
+            let current_plus_one = current.next(4, false);
             if let Some(current_plus_one) = current_plus_one {
-                let current_plus_two = current_plus_one.next(false);
+                let current_plus_two = current_plus_one.next(4, false);
 
                 let filtered: Vec<&Hai> = self
                     .juntehai
                     .iter()
                     .filter(|&x| x == &current_plus_one)
                     .collect();
-                if filtered.len() > 0 {
+                if !filtered.is_empty() {
                     handle_taatsu(
                         self,
                         decomposers_vec,
@@ -572,7 +843,7 @@ impl Tehai {
                             .iter()
                             .filter(|&x| x == &current_plus_two)
                             .collect();
-                        if filtered.len() > 0 {
+                        if !filtered.is_empty() {
                             handle_juntsu(
                                 self,
                                 decomposers_vec,
@@ -589,7 +860,7 @@ impl Tehai {
                         .iter()
                         .filter(|&x| x == &current_plus_two)
                         .collect();
-                    if filtered.len() > 0 {
+                    if !filtered.is_empty() {
                         handle_taatsu(
                             self,
                             decomposers_vec,
@@ -611,21 +882,30 @@ impl Tehai {
     }
 }
 
-impl std::fmt::Display for Tehai {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl StyledDisplay for Tehai {
+    fn fmt_styled(&self, f: &mut std::fmt::Formatter<'_>, style: HaiStyle) -> std::fmt::Result {
         let mut format_string = String::new();
 
         for hai in &self.juntehai {
-            format_string += &hai.to_string();
+            format_string += &hai.styled(style).to_string();
         }
-        for mentsu in &self.fuuro {
-            format_string += &mentsu.to_string();
+        for fuuro in &self.fuuro {
+            format_string += &fuuro.styled(style).to_string();
+        }
+        for _ in 0..self.nukidora {
+            format_string += &format!("[{}]", Hai::Jihai(4).styled(style));
         }
 
         write!(f, "{}", format_string)
     }
 }
 
+impl std::fmt::Display for Tehai {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_styled(f, HaiStyle::Text)
+    }
+}
+
 impl Decomposer {
     fn new() -> Self {
         Self {
@@ -684,7 +964,7 @@ impl MachiCondition {
     /// * nokori: 残り
     pub fn nokori(&self) -> usize {
         let mut nokori = 0;
-        for (_, number) in &self.machihai {
+        for number in self.machihai.values() {
             nokori += *number as usize;
         }
         nokori
@@ -702,21 +982,21 @@ impl MachiCondition {
         &mut self,
         decomposer: &Decomposer,
         juntehai_number: usize,
-    ) -> Result<&mut Self, String> {
+    ) -> Result<&mut Self, Error> {
         if let i32::MIN..=-1 = decomposer.shanten(juntehai_number) {
-            return Err("Logic Error: Code cannot reach here.".to_string());
+            return Err(Error::Logic("Code cannot reach here."));
         }
 
         // If invalid_ukihai_vec does not contain sutehai, no need to analyze.
         if !decomposer
             .invalid_ukihai_vec
-            .contains(&Ukihai { 0: self.sutehai })
+            .contains(&Ukihai(self.sutehai))
         {
             // But Chiitoitsu is a little special.
             if decomposer.hourakei == Hourakei::Chiitoitsu {
                 if !decomposer
                     .valid_ukihai_vec
-                    .contains(&Ukihai { 0: self.sutehai })
+                    .contains(&Ukihai(self.sutehai))
                 {
                     return Ok(self);
                 }
@@ -743,17 +1023,17 @@ impl MachiCondition {
     fn handle_taatsu(
         &mut self,
         decomposer: &Decomposer,
-    ) -> Result<&mut Self, String> {
+    ) -> Result<&mut Self, Error> {
         for taatsu in &decomposer.taatsu_vec {
             match (taatsu.0, taatsu.1) {
                 (Hai::Manzu(lhs), Hai::Manzu(rhs)) => {
                     if rhs - lhs == 2 {
                         self.machihai.insert(Hai::Manzu(lhs + 1), 4);
                     } else if rhs - lhs == 1 {
-                        if let Some(machi) = taatsu.0.previous(false) {
+                        if let Some(machi) = taatsu.0.previous(4, false) {
                             self.machihai.insert(machi, 4);
                         }
-                        if let Some(machi) = taatsu.1.next(false) {
+                        if let Some(machi) = taatsu.1.next(4, false) {
                             self.machihai.insert(machi, 4);
                         }
                     }
@@ -762,10 +1042,10 @@ impl MachiCondition {
                     if rhs - lhs == 2 {
                         self.machihai.insert(Hai::Pinzu(lhs + 1), 4);
                     } else if rhs - lhs == 1 {
-                        if let Some(machi) = taatsu.0.previous(false) {
+                        if let Some(machi) = taatsu.0.previous(4, false) {
                             self.machihai.insert(machi, 4);
                         }
-                        if let Some(machi) = taatsu.1.previous(false) {
+                        if let Some(machi) = taatsu.1.next(4, false) {
                             self.machihai.insert(machi, 4);
                         }
                     }
@@ -774,63 +1054,50 @@ impl MachiCondition {
                     if rhs - lhs == 2 {
                         self.machihai.insert(Hai::Souzu(lhs + 1), 4);
                     } else if rhs - lhs == 1 {
-                        if let Some(machi) = taatsu.0.previous(false) {
+                        if let Some(machi) = taatsu.0.previous(4, false) {
                             self.machihai.insert(machi, 4);
                         }
-                        if let Some(machi) = taatsu.1.next(false) {
+                        if let Some(machi) = taatsu.1.next(4, false) {
                             self.machihai.insert(machi, 4);
                         }
                     }
                 }
-                _ => return Err("Logic error: Code cannot reach here.".to_string()),
+                _ => return Err(Error::Logic("Code cannot reach here.")),
             }
         }
 
         Ok(self)
     }
 
-    fn finally(&mut self, tehai: &Tehai) {
-        // Remove hai whose number is 0.
-        let check_count = |machihai: &mut BTreeMap<_, _>, item| {
-            if machihai.contains_key(item) {
-                if machihai[item] > 1 {
-                    machihai.insert(*item, machihai[item] - 1);
-                } else if machihai[item] == 1 {
-                    machihai.remove(item);
-                }
-            }
-        };
-        for item in &tehai.juntehai {
-            check_count(&mut self.machihai, item);
+    fn finally(&mut self, tehai: &Tehai, taku: &Taku, shanten: i32) {
+        // Remove hai not used in current mode.
+        self.machihai
+            .retain(|hai, _| hai.is_valid(tehai.player_number));
+
+        // Furiten only matters when tenpai, and sutehai will be in kawa too.
+        if shanten == 0 {
+            self.furiten = self.machihai.keys().any(|machihai| {
+                *machihai == self.sutehai
+                    || taku.kawa.iter().any(|hai| hai.normalize() == *machihai)
+            });
         }
 
-        for mentsu in &tehai.fuuro {
-            match mentsu {
-                Mentsu::Juntsu(a, b, c) => {
-                    for item in vec![a, b, c] {
-                        check_count(&mut self.machihai, item);
-                    }
-                }
-                Mentsu::Koutsu(item) => {
-                    for _ in 0..3 {
-                        check_count(&mut self.machihai, item);
-                    }
-                }
-                Mentsu::Kantsu(item) => {
-                    for _ in 0..4 {
-                        check_count(&mut self.machihai, item);
-                    }
-                }
-            }
+        // Remove hai whose number is 0.
+        let mut used_hai = HaiCounts::from(&tehai.all_hai()[..]);
+        for hai in taku.visible_hai() {
+            used_hai.add(&hai);
+        }
+        for (hai, number) in self.machihai.iter_mut() {
+            *number = number.saturating_sub(used_hai.count_normalized(hai));
         }
-        
+        self.machihai.retain(|_, number| *number > 0);
     }
 
     fn handle_mentsute(
         &mut self,
         decomposer: &Decomposer,
         juntehai_number: usize,
-    ) -> Result<&mut Self, String> {
+    ) -> Result<&mut Self, Error> {
         let max_mentsu_toitsu_taatsu = (juntehai_number + 1) / 3;
 
         // If taatsu overload, no need to analyze.
@@ -882,15 +1149,15 @@ impl MachiCondition {
                         continue;
                     }
 
-                    if let Some(machi) = ukihai.0.previous(false) {
+                    if let Some(machi) = ukihai.0.previous(4, false) {
                         self.machihai.insert(machi, 4);
-                        if let Some(machi_2) = machi.previous(false) {
+                        if let Some(machi_2) = machi.previous(4, false) {
                             self.machihai.insert(machi_2, 4);
                         }
                     }
-                    if let Some(machi) = ukihai.0.next(false) {
+                    if let Some(machi) = ukihai.0.next(4, false) {
                         self.machihai.insert(machi, 4);
-                        if let Some(machi_2) = machi.next(false) {
+                        if let Some(machi_2) = machi.next(4, false) {
                             self.machihai.insert(machi_2, 4);
                         }
                     }
@@ -904,7 +1171,7 @@ impl MachiCondition {
     fn handle_chiitoitsu(
         &mut self,
         decomposer: &Decomposer,
-    ) -> Result<&mut Self, String> {
+    ) -> Result<&mut Self, Error> {
         // Enough single hai.
         if decomposer.toitsu_vec.len() + decomposer.valid_ukihai_vec.len() >= 7 {
             for Ukihai(hai) in &decomposer.valid_ukihai_vec {
@@ -915,7 +1182,7 @@ impl MachiCondition {
         }
         // Need more single hai for shanten.
         else {
-            let mut all_hai = Hai::all_type();
+            let mut all_hai = Hai::all_type(4);
 
             // Not wait hai that already been pairs.
             for toitsu in decomposer.toitsu_vec.iter() {
@@ -931,7 +1198,7 @@ impl MachiCondition {
         Ok(self)
     }
 
-    fn handle_kokushimusou(&mut self, decomposer: &Decomposer) -> Result<&mut Self, String> {
+    fn handle_kokushimusou(&mut self, decomposer: &Decomposer) -> Result<&mut Self, Error> {
         let yaochuupai_type = Hai::yaochuupai_type();
         let mut yaochuupai_iter = yaochuupai_type.iter();
         let mut kokushimusou_valid_iter = decomposer.valid_ukihai_vec.iter();
@@ -966,7 +1233,7 @@ impl MachiCondition {
         let mut kokushimusou_valid_value = kokushimusou_valid_iter.next();
         let mut yaochuupai_used = false;
 
-        while yaochuupai_value != None && kokushimusou_valid_value != None {
+        while yaochuupai_value.is_some() && kokushimusou_valid_value.is_some() {
             if let (Some(lhs), Some(Ukihai(rhs))) = (yaochuupai_value, kokushimusou_valid_value) {
                 if lhs < rhs {
                     if !yaochuupai_used {
@@ -995,13 +1262,13 @@ impl MachiCondition {
     }
 }
 
-impl std::fmt::Display for MachiCondition {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl StyledDisplay for MachiCondition {
+    fn fmt_styled(&self, f: &mut std::fmt::Formatter<'_>, style: HaiStyle) -> std::fmt::Result {
         let mut machihai_string = String::new();
         let mut furiten_string = String::new();
         let mut nokori = 0;
         for (machihai, number) in self.machihai.iter() {
-            machihai_string += &machihai.to_string();
+            machihai_string += &machihai.styled(style).to_string();
             machihai_string += " ";
             nokori += *number as usize;
         }
@@ -1011,10 +1278,209 @@ impl std::fmt::Display for MachiCondition {
         write!(
             f,
             "打 {} 摸 {} 共{}枚{}",
-            self.sutehai.to_string(),
+            self.sutehai.styled(style),
             machihai_string,
             nokori,
             furiten_string
         )
     }
 }
+
+impl std::fmt::Display for MachiCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_styled(f, HaiStyle::Text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse, print compactly and parse again.
+    fn assert_round_trip(input: &str, player_number: u8, compact: &str) {
+        let tehai = Tehai::with_player_number(input.to_string(), player_number).unwrap();
+        assert_eq!(tehai.to_compact_string(), compact, "{}", input);
+        let parsed = Tehai::with_player_number(compact.to_string(), player_number).unwrap();
+        assert_eq!(parsed, tehai, "{}", input);
+        assert_eq!(parsed.to_compact_string(), compact, "{}", input);
+    }
+
+    #[test]
+    fn compact_juntehai() {
+        assert_round_trip("1122345m4689s357p", 4, "1122345m357p4689s");
+        assert_round_trip("1m2m3m4m4m5m4p4p4p5p8s", 4, "123445m4445p8s");
+        assert_round_trip("45p 8s14 4m2 5m44p 3m", 4, "123445m4445p8s");
+        assert_round_trip("11223344556677z", 4, "11223344556677z");
+        assert_round_trip("19m19p19s12345677z", 4, "19m19p19s12345677z");
+        assert_round_trip("5z", 4, "5z");
+        assert_round_trip("", 4, "");
+    }
+
+    #[test]
+    fn compact_aka() {
+        assert_round_trip("123406m4440p8s", 4, "123406m4440p8s");
+        assert_round_trip("0m5m5m0p0s", 4, "550m0p0s");
+    }
+
+    #[test]
+    fn compact_fuuro() {
+        assert_round_trip("123445m4445p8s[111z]", 4, "123445m4445p8s[111z]");
+        assert_round_trip(
+            "1z[1m2m3m][4p5p6p][7s8s9s][2z2z2z]",
+            4,
+            "1z[123m][456p][789s][222z]",
+        );
+        assert_round_trip("45p 8s14 4m[11 1z]2 5m44p 3m", 4, "123445m4445p8s[111z]");
+        assert_round_trip("11z[<312s]", 4, "11z[12<3s]");
+        assert_round_trip("11z[3<12s]", 4, "11z[<123s]");
+        assert_round_trip("11z[2^22z]", 4, "11z[^222z]");
+        assert_round_trip("11z[5^50p]", 4, "11z[^550p]");
+        assert_round_trip("11z[555>0p]", 4, "11z[555>0p]");
+        assert_round_trip("11z[>5555p]", 4, "11z[>5555p]");
+        assert_round_trip("11z[^555+5p]", 4, "11z[^555+5p]");
+        assert_round_trip("11z[5+55^0p]", 4, "11z[+555^0p]");
+        assert_round_trip("11z(7777z)", 4, "11z(7777z)");
+        assert_round_trip("11z(5550s)[<406m]", 4, "11z(5550s)[<406m]");
+    }
+
+    #[test]
+    fn compact_sanma() {
+        assert_round_trip("19m123456p789s11z[4z][4z]", 3, "19m123456p789s11z[4z][4z]");
+        assert_round_trip(
+            "[4z]19m123456p789s11z(7777z)",
+            3,
+            "19m123456p789s11z(7777z)[4z]",
+        );
+    }
+
+    #[test]
+    fn compact_display_parsed() {
+        for input in ["123406m4440p8s[111z]", "11z[3<12s][^555+0p](9999s)"] {
+            let tehai = Tehai::new(input.to_string()).unwrap();
+            let displayed = Tehai::new(tehai.to_string()).unwrap();
+            assert_eq!(displayed, tehai, "{}", input);
+            assert_eq!(displayed.to_compact_string(), tehai.to_compact_string());
+        }
+    }
+
+    #[test]
+    fn analyze_same_as_ukeire() {
+        let taku = Taku::default();
+        for input in [
+            "234m567s11z23p789s9m",
+            "234m567s11z78p789s9m",
+            "1357m2468p1357s12z",
+        ] {
+            let tehai = Tehai::new(input.to_string()).unwrap();
+            let (shanten, conditions) = tehai.analyze(&taku).unwrap();
+            for condition in conditions {
+                let mut rest = tehai.clone();
+                remove_once(&mut rest.juntehai, &condition.sutehai);
+                let ukeire = rest.ukeire(&taku).unwrap();
+                assert_eq!(ukeire.shanten, shanten, "{} {}", input, condition.sutehai);
+                assert_eq!(
+                    condition.machihai, ukeire.machihai,
+                    "{} {}",
+                    input, condition.sutehai
+                );
+            }
+        }
+
+        let tehai = Tehai::new("234m567s11z23p789s9m".to_string()).unwrap();
+        let (_, conditions) = tehai.analyze(&taku).unwrap();
+        let machihai: Vec<Hai> = conditions[0].machihai.keys().copied().collect();
+        assert_eq!(conditions[0].sutehai, Hai::Manzu(9));
+        assert_eq!(machihai, [Hai::Pinzu(1), Hai::Pinzu(4)]);
+    }
+
+    #[test]
+    fn too_many_hai() {
+        let parse = |input: &str| Tehai::new(input.to_string()).unwrap_err();
+        let error = parse("11111m222p");
+        assert_eq!(
+            error,
+            Error::TooManyHai {
+                hai: Hai::Manzu(1),
+                number: 5,
+                index: Some(4)
+            }
+        );
+        assert_eq!(error.index(), Some(4));
+        assert_eq!(error.hai(), Some(Hai::Manzu(1)));
+
+        let error = parse("5m5mr 4m0m");
+        assert_eq!(error.index(), Some(8));
+        assert_eq!(error.hai(), Some(Hai::Manzu(0)));
+        assert_eq!(parse("1z[111z](1111z)").index(), Some(9));
+        assert_eq!(parse("EEE 1z[2z2z E]").index(), Some(13));
+
+        let tehai = Tehai::new("1111m".to_string()).unwrap();
+        let taku = Taku {
+            dora_hyouji: vec![Hai::Manzu(1)],
+            ..Taku::default()
+        };
+        let error = tehai.check_taku(&taku).unwrap_err();
+        assert_eq!(error.index(), None);
+        assert_eq!(error.hai(), Some(Hai::Manzu(1)));
+    }
+
+    /// Parse both and compare.
+    fn assert_same(input: &str, player_number: u8, standard: &str) {
+        let tehai = Tehai::with_player_number(input.to_string(), player_number).unwrap();
+        let expected = Tehai::with_player_number(standard.to_string(), player_number).unwrap();
+        assert_eq!(tehai, expected, "{}", input);
+    }
+
+    #[test]
+    fn alias_jihai() {
+        assert_same("123m456p789sESWNPFC", 4, "123m456p789s1234567z");
+        assert_same("123m456p789sEE[^SSS]", 4, "123m456p789s11z[2^22z]");
+        assert_same("1m E 2m (CCCC)", 4, "12m1z(7777z)");
+        assert_same("19m123456p789sEE[N][N]", 3, "19m123456p789s11z[4z][4z]");
+    }
+
+    #[test]
+    fn alias_mjai_aka() {
+        assert_same("5mr5pr5sr", 4, "0m0p0s");
+        assert_same("1234m5mr6m[<5pr46p]", 4, "123406m[<046p]");
+        assert_same("[5sr5s5s]", 4, "[055s]");
+    }
+
+    #[test]
+    fn alias_unicode() {
+        assert_same("🀇🀈🀉🀏🀙🀡🀐🀘", 4, "1239m19p19s");
+        assert_same("🀀🀁🀂🀃🀆🀅🀄", 4, "1234567z");
+        assert_same("🀄️🀄️🀄️ 🀋r🀋 [<🀍🀋🀌]", 4, "777z05m[<756m]");
+        assert_same("🀀🀀1m", 4, "11z1m");
+    }
+
+    #[test]
+    fn alias_error() {
+        let parse = |input: &str, player_number| {
+            Tehai::with_player_number(input.to_string(), player_number).unwrap_err()
+        };
+        assert_eq!(parse("55mr", 4), Error::UnusedAkaCharacter { index: 3 });
+        assert_eq!(parse("4mr", 4), Error::UnusedAkaCharacter { index: 2 });
+        assert_eq!(parse("0mr", 4), Error::UnusedAkaCharacter { index: 2 });
+        assert_eq!(parse("5m r", 4), Error::UnusedAkaCharacter { index: 3 });
+        assert_eq!(parse("Er", 4), Error::UnusedAkaCharacter { index: 1 });
+        assert_eq!(
+            parse("123S", 4),
+            Error::MissingTypeCharacter {
+                chr: Some('S'),
+                index: 3
+            }
+        );
+        assert_eq!(
+            parse("🀊", 3),
+            Error::InvalidHai {
+                hai: Hai::Manzu(4),
+                index: 0
+            }
+        );
+        assert_eq!(
+            parse("e", 4),
+            Error::UnknownCharacter { chr: 'e', index: 0 }
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use super::{Error, Hai, HaiCounts, HaiStyle, Machi, StyledDisplay, Taku, Tehai};
+use std::collections::BTreeMap;
+
+/// Hai accepted by a 3*k+1 tehai, which is waiting for a hai to be drawn.
+///
+/// # Japanese
+/// * ukeire: 受け入れ
+/// * machihai: 待ち牌
+/// * furiten: 振り聴
+///
+/// # Member
+/// * shanten: the number of shanten. 0 means tenpai.
+/// * machihai: hai reducing shanten, or winning hai when tenpai, with the number left.
+/// * machi: every winning hai with all shapes of waiting, only when tenpai. Hai all used
+///   up are included too.
+/// * furiten: if tenpai but any winning hai in kawa.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ukeire {
+    pub shanten: i32,
+    pub machihai: BTreeMap<Hai, u8>,
+    pub machi: BTreeMap<Hai, Vec<Machi>>,
+    pub furiten: bool,
+}
+
+impl Ukeire {
+    /// Get how many hai can waiting for.
+    ///
+    /// # Japanese
+    /// * nokori: 残り
+    pub fn nokori(&self) -> usize {
+        self.machihai.values().map(|number| *number as usize).sum()
+    }
+}
+
+impl Tehai {
+    /// Analyze a 3*k+1 tehai for shanten and ukeire, without discarding.
+    ///
+    /// Every valid hai is tried to be drawn, and the ones reducing shanten are accepted.
+    /// Hai visible on taku are not counted, same as `analyze`.
+    pub fn ukeire(&self, taku: &Taku) -> Result<Ukeire, Error> {
+        if self.juntehai.len() % 3 != 1 {
+            return Err(Error::WrongHaiNumber {
+                remainder: 1,
+                found: self.juntehai.len(),
+            });
+        }
+        self.check_taku(taku)?;
+
+        let tehai = self.normalize();
+        let mut used_hai = HaiCounts::from(&tehai.all_hai()[..]);
+        for hai in taku.visible_hai() {
+            used_hai.add(&hai);
+        }
+        let count = |hai: &Hai| used_hai.count_normalized(hai);
+        let tehai_counts = HaiCounts::from(&tehai.all_hai()[..]);
+
+        // Shanten after drawing each hai, the fifth one with fuuro excluded.
+        let mut drawn_shanten = vec![];
+        for hai in Hai::all_type(self.player_number) {
+            if tehai_counts.count(&hai) >= 4 {
+                continue;
+            }
+            let mut drawn = tehai.clone();
+            drawn.juntehai.push(hai);
+            drawn.juntehai.sort();
+            drawn_shanten.push((hai, drawn.shanten()?, drawn));
+        }
+        let min_shanten = drawn_shanten
+            .iter()
+            .map(|(_, shanten, _)| *shanten)
+            .min()
+            .ok_or(Error::Logic("No hai can be drawn."))?;
+
+        let mut ukeire = Ukeire {
+            shanten: min_shanten + 1,
+            machihai: BTreeMap::new(),
+            machi: BTreeMap::new(),
+            furiten: false,
+        };
+        for (hai, shanten, drawn) in drawn_shanten {
+            if shanten != min_shanten {
+                continue;
+            }
+            if 4 > count(&hai) {
+                ukeire.machihai.insert(hai, 4 - count(&hai));
+            }
+            if shanten == -1 {
+                let mut machi_vec = vec![];
+                for decomposer in drawn.agari_decompose(hai)? {
+                    if !machi_vec.contains(&decomposer.machi) {
+                        machi_vec.push(decomposer.machi);
+                    }
+                }
+                ukeire.machi.insert(hai, machi_vec);
+            }
+        }
+        ukeire.furiten = ukeire.shanten == 0
+            && taku
+                .kawa
+                .iter()
+                .any(|hai| ukeire.machi.contains_key(&hai.normalize()));
+
+        Ok(ukeire)
+    }
+}
+
+/// Print like `摸 1m 4m  共5枚`, and each winning hai with shapes of waiting when tenpai.
+impl StyledDisplay for Ukeire {
+    fn fmt_styled(&self, f: &mut std::fmt::Formatter<'_>, style: HaiStyle) -> std::fmt::Result {
+        let mut machihai_string = String::new();
+        for machihai in self.machihai.keys() {
+            machihai_string += &machihai.styled(style).to_string();
+            machihai_string += " ";
+        }
+        write!(f, "摸 {} 共{}枚", machihai_string, self.nokori())?;
+        if self.furiten {
+            write!(f, "!振り聴!")?;
+        }
+        for (hai, machi_vec) in &self.machi {
+            write!(f, "\n{}", hai.styled(style))?;
+            for machi in machi_vec {
+                write!(f, " {}", machi)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Ukeire {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_styled(f, HaiStyle::Text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ukeire(tehai: &str, taku: &Taku) -> Ukeire {
+        Tehai::new(tehai.to_string()).unwrap().ukeire(taku).unwrap()
+    }
+
+    #[test]
+    fn tenpai() {
+        let taku = Taku {
+            tacha_kawa: vec![Hai::Manzu(2), Hai::Manzu(2)],
+            ..Taku::default()
+        };
+        let tenpai = ukeire("3444m123p456p789s", &taku);
+        assert_eq!(tenpai.shanten, 0);
+        assert_eq!(
+            tenpai.machihai.into_iter().collect::<Vec<_>>(),
+            [(Hai::Manzu(2), 2), (Hai::Manzu(3), 3), (Hai::Manzu(5), 4)]
+        );
+        assert_eq!(
+            tenpai.machi.into_iter().collect::<Vec<_>>(),
+            [
+                (Hai::Manzu(2), vec![Machi::Ryanmen]),
+                (Hai::Manzu(3), vec![Machi::Tanki]),
+                (Hai::Manzu(5), vec![Machi::Ryanmen]),
+            ]
+        );
+        assert!(!tenpai.furiten);
+
+        // Winning hai all used up are still machi.
+        let taku = Taku {
+            tacha_kawa: vec![Hai::Jihai(1); 2],
+            ..Taku::default()
+        };
+        let shanpon = ukeire("123m456p789s1122z", &taku);
+        assert_eq!(
+            shanpon.machihai.into_iter().collect::<Vec<_>>(),
+            [(Hai::Jihai(2), 2)]
+        );
+        assert_eq!(
+            shanpon.machi.into_iter().collect::<Vec<_>>(),
+            [
+                (Hai::Jihai(1), vec![Machi::Shanpon]),
+                (Hai::Jihai(2), vec![Machi::Shanpon]),
+            ]
+        );
+    }
+
+    #[test]
+    fn furiten() {
+        let mut taku = Taku {
+            kawa: vec![Hai::Souzu(9)],
+            ..Taku::default()
+        };
+        let tehai = "3444m123p456p789s";
+        assert!(!ukeire(tehai, &taku).furiten);
+        taku.kawa.push(Hai::Manzu(0));
+        let furiten = ukeire(tehai, &taku);
+        assert!(furiten.furiten);
+        assert_eq!(furiten.machihai[&Hai::Manzu(5)], 3);
+
+        // Not furiten before tenpai.
+        let iishanten = ukeire("3446m123p456p789s", &taku);
+        assert_eq!(iishanten.shanten, 1);
+        assert!(iishanten.machi.is_empty());
+        assert!(!iishanten.furiten);
+    }
+
+    #[test]
+    fn fifth_hai_with_fuuro() {
+        // Waiting only for the fifth 1z, so not tenpai, same as `machihai`.
+        let tehai = "1z123m456p789s[111z]";
+        let ukeire = ukeire(tehai, &Taku::default());
+        assert_eq!(ukeire.shanten, 1);
+        assert!(!ukeire.machihai.contains_key(&Hai::Jihai(1)));
+        assert!(ukeire.machi.is_empty());
+        assert!(Tehai::new(tehai.to_string())
+            .unwrap()
+            .machihai()
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,841 @@
+use super::agari::{AgariDecomposer, Machi};
+use super::tehai::Hourakei;
+use super::{Hai, Mentsu};
+
+/// Condition when winning.
+///
+/// # Japanese
+/// * agari_hai: 和了牌
+/// * tsumo: 自摸
+/// * riichi: 立直
+/// * double_riichi: 両立直
+/// * ippatsu: 一発
+/// * haitei: 海底
+/// * rinshan: 嶺上
+/// * chankan: 槍槓
+/// * tenhou: 天和
+/// * bakaze: 場風
+/// * jikaze: 自風
+/// * dora_hyouji: ドラ表示牌
+/// * uradora_hyouji: 裏ドラ表示牌
+/// * honba: 本場
+/// * kyoutaku: 供託
+///
+/// # Member
+/// * agari_hai: the hai completed the tehai.
+/// * tsumo: winning by self draw, otherwise by ron.
+/// * riichi: riichi declared.
+/// * double_riichi: riichi declared on the first uninterrupted turn.
+/// * ippatsu: winning within one uninterrupted turn after riichi.
+/// * haitei: winning with the last hai of the wall, by tsumo or by ron.
+/// * rinshan: winning with the hai drawn after kan.
+/// * chankan: winning by robbing a kan.
+/// * tenhou: winning on the first uninterrupted draw. It means tenhou for oya and chiihou for ko.
+/// * bakaze: wind of the round, 1\~4z.
+/// * jikaze: wind of the player, 1\~4z. Player whose jikaze is 1z is oya.
+/// * dora_hyouji: indicators of dora.
+/// * uradora_hyouji: indicators of uradora, only counted with riichi.
+/// * honba: number of honba sticks on the table.
+/// * kyoutaku: number of riichi sticks on the table, all taken by the winner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgariCondition {
+    pub agari_hai: Hai,
+    pub tsumo: bool,
+    pub riichi: bool,
+    pub double_riichi: bool,
+    pub ippatsu: bool,
+    pub haitei: bool,
+    pub rinshan: bool,
+    pub chankan: bool,
+    pub tenhou: bool,
+    pub bakaze: Hai,
+    pub jikaze: Hai,
+    pub dora_hyouji: Vec<Hai>,
+    pub uradora_hyouji: Vec<Hai>,
+    pub honba: u8,
+    pub kyoutaku: u8,
+}
+
+/// All yaku.
+///
+/// # Japanese
+/// * Yaku: 役
+/// * Riichi: 立直
+/// * DoubleRiichi: 両立直
+/// * Ippatsu: 一発
+/// * MenzenTsumo: 門前清自摸和
+/// * Tanyao: 断幺九
+/// * Pinfu: 平和
+/// * Iipeikou: 一盃口
+/// * Bakaze: 場風
+/// * Jikaze: 自風
+/// * Sangenpai: 三元牌
+/// * Haitei: 海底摸月
+/// * Houtei: 河底撈魚
+/// * Rinshan: 嶺上開花
+/// * Chankan: 槍槓
+/// * Chiitoitsu: 七対子
+/// * Sanshokudoujun: 三色同順
+/// * Ittsu: 一気通貫
+/// * Chanta: 混全帯幺九
+/// * Toitoi: 対々和
+/// * Sanankou: 三暗刻
+/// * Sanshokudoukou: 三色同刻
+/// * Sankantsu: 三槓子
+/// * Shousangen: 小三元
+/// * Honroutou: 混老頭
+/// * Ryanpeikou: 二盃口
+/// * Junchan: 純全帯幺九
+/// * Honitsu: 混一色
+/// * Chinitsu: 清一色
+/// * Kokushimusou: 国士無双
+/// * KokushimusouJuusanmen: 国士無双十三面
+/// * Suuankou: 四暗刻
+/// * SuuankouTanki: 四暗刻単騎
+/// * Daisangen: 大三元
+/// * Shousuushii: 小四喜
+/// * Daisuushii: 大四喜
+/// * Tsuuiisou: 字一色
+/// * Ryuuiisou: 緑一色
+/// * Chinroutou: 清老頭
+/// * Chuurenpoutou: 九蓮宝燈
+/// * JunseiChuurenpoutou: 純正九蓮宝燈
+/// * Suukantsu: 四槓子
+/// * Tenhou: 天和
+/// * Chiihou: 地和
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Yaku {
+    Riichi,
+    DoubleRiichi,
+    Ippatsu,
+    MenzenTsumo,
+    Tanyao,
+    Pinfu,
+    Iipeikou,
+    Bakaze(Hai),
+    Jikaze(Hai),
+    Sangenpai(Hai),
+    Haitei,
+    Houtei,
+    Rinshan,
+    Chankan,
+    Chiitoitsu,
+    Sanshokudoujun,
+    Ittsu,
+    Chanta,
+    Toitoi,
+    Sanankou,
+    Sanshokudoukou,
+    Sankantsu,
+    Shousangen,
+    Honroutou,
+    Ryanpeikou,
+    Junchan,
+    Honitsu,
+    Chinitsu,
+    Kokushimusou,
+    KokushimusouJuusanmen,
+    Suuankou,
+    SuuankouTanki,
+    Daisangen,
+    Shousuushii,
+    Daisuushii,
+    Tsuuiisou,
+    Ryuuiisou,
+    Chinroutou,
+    Chuurenpoutou,
+    JunseiChuurenpoutou,
+    Suukantsu,
+    Tenhou,
+    Chiihou,
+}
+
+impl AgariCondition {
+    /// Create a condition without any special situation, for ko sitting south in east round.
+    pub fn new(agari_hai: Hai, tsumo: bool) -> Self {
+        Self {
+            agari_hai,
+            tsumo,
+            riichi: false,
+            double_riichi: false,
+            ippatsu: false,
+            haitei: false,
+            rinshan: false,
+            chankan: false,
+            tenhou: false,
+            bakaze: Hai::Jihai(1),
+            jikaze: Hai::Jihai(2),
+            dora_hyouji: vec![],
+            uradora_hyouji: vec![],
+            honba: 0,
+            kyoutaku: 0,
+        }
+    }
+
+    /// Return if the player is oya.
+    ///
+    /// # Japanese
+    /// * oya: 親
+    pub fn is_oya(&self) -> bool {
+        self.jikaze == Hai::Jihai(1)
+    }
+}
+
+impl Yaku {
+    /// Return the han of yaku. Yakuman will be 13 for each multiple.
+    ///
+    /// # Parameters
+    /// * menzen: If false, some yaku lose one han, and menzen-only yaku will be 0.
+    ///
+    /// # Japanese
+    /// * han: 飜
+    /// * menzen: 門前
+    /// * kuisagari: 食い下がり
+    pub fn han(&self, menzen: bool) -> u8 {
+        if self.is_yakuman() {
+            return 13 * self.yakuman();
+        }
+
+        // Han of menzen and han of kuisagari.
+        let (menzen_han, kui_han) = match self {
+            Yaku::Riichi | Yaku::Ippatsu | Yaku::MenzenTsumo | Yaku::Pinfu | Yaku::Iipeikou => {
+                (1, 0)
+            }
+            Yaku::DoubleRiichi | Yaku::Chiitoitsu => (2, 0),
+            Yaku::Ryanpeikou => (3, 0),
+            Yaku::Tanyao
+            | Yaku::Bakaze(_)
+            | Yaku::Jikaze(_)
+            | Yaku::Sangenpai(_)
+            | Yaku::Haitei
+            | Yaku::Houtei
+            | Yaku::Rinshan
+            | Yaku::Chankan => (1, 1),
+            Yaku::Sanshokudoujun | Yaku::Ittsu | Yaku::Chanta => (2, 1),
+            Yaku::Toitoi
+            | Yaku::Sanankou
+            | Yaku::Sanshokudoukou
+            | Yaku::Sankantsu
+            | Yaku::Shousangen
+            | Yaku::Honroutou => (2, 2),
+            Yaku::Junchan | Yaku::Honitsu => (3, 2),
+            Yaku::Chinitsu => (6, 5),
+            _ => (0, 0),
+        };
+
+        if menzen {
+            menzen_han
+        } else {
+            kui_han
+        }
+    }
+
+    /// Return the multiple of yakuman, 0 for normal yaku.
+    pub fn yakuman(&self) -> u8 {
+        match self {
+            Yaku::KokushimusouJuusanmen
+            | Yaku::SuuankouTanki
+            | Yaku::Daisuushii
+            | Yaku::JunseiChuurenpoutou => 2,
+            Yaku::Kokushimusou
+            | Yaku::Suuankou
+            | Yaku::Daisangen
+            | Yaku::Shousuushii
+            | Yaku::Tsuuiisou
+            | Yaku::Ryuuiisou
+            | Yaku::Chinroutou
+            | Yaku::Chuurenpoutou
+            | Yaku::Suukantsu
+            | Yaku::Tenhou
+            | Yaku::Chiihou => 1,
+            _ => 0,
+        }
+    }
+
+    /// Return if self is yakuman.
+    ///
+    /// # Japanese
+    /// * yakuman: 役満
+    pub fn is_yakuman(&self) -> bool {
+        self.yakuman() > 0
+    }
+
+    /// Judge all yaku of one reading of a complete tehai, sorted.
+    ///
+    /// If any yakuman found, only yakuman will be returned.
+    pub fn judge(decomposer: &AgariDecomposer, condition: &AgariCondition) -> Vec<Yaku> {
+        let mut yaku_vec = Self::judge_yakuman(decomposer, condition);
+        if yaku_vec.is_empty() {
+            yaku_vec = Self::judge_normal(decomposer, condition);
+        }
+        yaku_vec.sort();
+        yaku_vec
+    }
+
+    fn judge_normal(decomposer: &AgariDecomposer, condition: &AgariCondition) -> Vec<Yaku> {
+        let menzen = decomposer.menzen();
+        let all_mentsu = decomposer.all_mentsu();
+        let all_hai = all_hai(decomposer);
+        let mut yaku_vec = vec![];
+
+        // Yaku of situation.
+        if menzen {
+            if condition.double_riichi {
+                yaku_vec.push(Yaku::DoubleRiichi);
+            } else if condition.riichi {
+                yaku_vec.push(Yaku::Riichi);
+            }
+            if condition.ippatsu && (condition.riichi || condition.double_riichi) {
+                yaku_vec.push(Yaku::Ippatsu);
+            }
+            if condition.tsumo {
+                yaku_vec.push(Yaku::MenzenTsumo);
+            }
+        }
+        if condition.haitei && !condition.rinshan {
+            yaku_vec.push(if condition.tsumo {
+                Yaku::Haitei
+            } else {
+                Yaku::Houtei
+            });
+        }
+        if condition.rinshan && condition.tsumo {
+            yaku_vec.push(Yaku::Rinshan);
+        }
+        if condition.chankan && !condition.tsumo {
+            yaku_vec.push(Yaku::Chankan);
+        }
+
+        // Yaku of hai.
+        if all_hai.iter().all(|hai| !hai.is_yaochuupai()) {
+            yaku_vec.push(Yaku::Tanyao);
+        }
+        if all_hai.iter().all(|hai| hai.is_yaochuupai()) {
+            yaku_vec.push(Yaku::Honroutou);
+        }
+        let suit_number = suit_number(&all_hai);
+        if suit_number == 1 {
+            if all_hai.iter().any(|hai| hai.is_jihai()) {
+                yaku_vec.push(Yaku::Honitsu);
+            } else {
+                yaku_vec.push(Yaku::Chinitsu);
+            }
+        }
+
+        if decomposer.hourakei == Hourakei::Chiitoitsu {
+            yaku_vec.push(Yaku::Chiitoitsu);
+            return yaku_vec;
+        }
+
+        // Yaku of mentsu.
+        let jantou = decomposer.toitsu_vec[0].0;
+        let is_yakuhai =
+            |hai: Hai| hai.is_sangenpai() || hai == condition.bakaze || hai == condition.jikaze;
+
+        for mentsu in &all_mentsu {
+            if let Mentsu::Koutsu(hai) | Mentsu::Kantsu(hai) = *mentsu {
+                if hai.is_sangenpai() {
+                    yaku_vec.push(Yaku::Sangenpai(hai));
+                }
+                if hai == condition.bakaze {
+                    yaku_vec.push(Yaku::Bakaze(hai));
+                }
+                if hai == condition.jikaze {
+                    yaku_vec.push(Yaku::Jikaze(hai));
+                }
+            }
+        }
+
+        if menzen
+            && decomposer.machi == Machi::Ryanmen
+            && !is_yakuhai(jantou)
+            && all_mentsu
+                .iter()
+                .all(|mentsu| matches!(mentsu, Mentsu::Juntsu(..)))
+        {
+            yaku_vec.push(Yaku::Pinfu);
+        }
+
+        if menzen {
+            let mut peikou = 0;
+            let mut juntsu_vec: Vec<&Mentsu> = decomposer
+                .mentsu_vec
+                .iter()
+                .filter(|mentsu| matches!(mentsu, Mentsu::Juntsu(..)))
+                .collect();
+            while let Some(juntsu) = juntsu_vec.pop() {
+                if let Some(index) = juntsu_vec.iter().position(|other| *other == juntsu) {
+                    juntsu_vec.remove(index);
+                    peikou += 1;
+                }
+            }
+            if peikou == 2 {
+                yaku_vec.push(Yaku::Ryanpeikou);
+            } else if peikou == 1 {
+                yaku_vec.push(Yaku::Iipeikou);
+            }
+        }
+
+        let juntsu_first: Vec<Hai> = all_mentsu
+            .iter()
+            .filter_map(|mentsu| match mentsu {
+                Mentsu::Juntsu(first, _, _) => Some(*first),
+                _ => None,
+            })
+            .collect();
+        let koutsu_hai: Vec<Hai> = all_mentsu
+            .iter()
+            .filter_map(|mentsu| match mentsu {
+                Mentsu::Koutsu(hai) | Mentsu::Kantsu(hai) => Some(*hai),
+                _ => None,
+            })
+            .collect();
+
+        if (1..=7).any(|num| {
+            juntsu_first.contains(&Hai::Manzu(num))
+                && juntsu_first.contains(&Hai::Pinzu(num))
+                && juntsu_first.contains(&Hai::Souzu(num))
+        }) {
+            yaku_vec.push(Yaku::Sanshokudoujun);
+        }
+        if (1..=9).any(|num| {
+            koutsu_hai.contains(&Hai::Manzu(num))
+                && koutsu_hai.contains(&Hai::Pinzu(num))
+                && koutsu_hai.contains(&Hai::Souzu(num))
+        }) {
+            yaku_vec.push(Yaku::Sanshokudoukou);
+        }
+        for suit in [Hai::Manzu, Hai::Pinzu, Hai::Souzu] {
+            if [1, 4, 7]
+                .iter()
+                .all(|num| juntsu_first.contains(&suit(*num)))
+            {
+                yaku_vec.push(Yaku::Ittsu);
+            }
+        }
+
+        if !juntsu_first.is_empty()
+            && jantou.is_yaochuupai()
+            && all_mentsu
+                .iter()
+                .all(|mentsu| mentsu.hai_vec().iter().any(|hai| hai.is_yaochuupai()))
+        {
+            if all_hai.iter().any(|hai| hai.is_jihai()) {
+                yaku_vec.push(Yaku::Chanta);
+            } else {
+                yaku_vec.push(Yaku::Junchan);
+            }
+        }
+
+        if juntsu_first.is_empty() {
+            yaku_vec.push(Yaku::Toitoi);
+        }
+        if ankou_number(decomposer, condition) == 3 {
+            yaku_vec.push(Yaku::Sanankou);
+        }
+        if kantsu_number(decomposer) == 3 {
+            yaku_vec.push(Yaku::Sankantsu);
+        }
+        if koutsu_hai.iter().filter(|hai| hai.is_sangenpai()).count() == 2 && jantou.is_sangenpai()
+        {
+            yaku_vec.push(Yaku::Shousangen);
+        }
+
+        yaku_vec
+    }
+
+    fn judge_yakuman(decomposer: &AgariDecomposer, condition: &AgariCondition) -> Vec<Yaku> {
+        let menzen = decomposer.menzen();
+        let all_hai = all_hai(decomposer);
+        let mut yaku_vec = vec![];
+
+        if condition.tenhou && condition.tsumo && menzen {
+            yaku_vec.push(if condition.is_oya() {
+                Yaku::Tenhou
+            } else {
+                Yaku::Chiihou
+            });
+        }
+
+        if decomposer.hourakei == Hourakei::Kokushimusou {
+            yaku_vec.push(if decomposer.machi == Machi::Juusanmen {
+                Yaku::KokushimusouJuusanmen
+            } else {
+                Yaku::Kokushimusou
+            });
+            return yaku_vec;
+        }
+
+        if all_hai.iter().all(|hai| hai.is_jihai()) {
+            yaku_vec.push(Yaku::Tsuuiisou);
+        }
+        if all_hai.iter().all(|hai| hai.is_routouhai()) {
+            yaku_vec.push(Yaku::Chinroutou);
+        }
+        let green = [
+            Hai::Souzu(2),
+            Hai::Souzu(3),
+            Hai::Souzu(4),
+            Hai::Souzu(6),
+            Hai::Souzu(8),
+            Hai::Jihai(6),
+        ];
+        if all_hai.iter().all(|hai| green.contains(hai)) {
+            yaku_vec.push(Yaku::Ryuuiisou);
+        }
+
+        if decomposer.hourakei == Hourakei::Chiitoitsu {
+            return yaku_vec;
+        }
+
+        let jantou = decomposer.toitsu_vec[0].0;
+        let koutsu_hai: Vec<Hai> = decomposer
+            .all_mentsu()
+            .iter()
+            .filter_map(|mentsu| match mentsu {
+                Mentsu::Koutsu(hai) | Mentsu::Kantsu(hai) => Some(*hai),
+                _ => None,
+            })
+            .collect();
+
+        if ankou_number(decomposer, condition) == 4 {
+            yaku_vec.push(if decomposer.machi == Machi::Tanki {
+                Yaku::SuuankouTanki
+            } else {
+                Yaku::Suuankou
+            });
+        }
+        if kantsu_number(decomposer) == 4 {
+            yaku_vec.push(Yaku::Suukantsu);
+        }
+        if koutsu_hai.iter().filter(|hai| hai.is_sangenpai()).count() == 3 {
+            yaku_vec.push(Yaku::Daisangen);
+        }
+        let kazehai_koutsu = koutsu_hai.iter().filter(|hai| hai.is_kazehai()).count();
+        if kazehai_koutsu == 4 {
+            yaku_vec.push(Yaku::Daisuushii);
+        } else if kazehai_koutsu == 3 && jantou.is_kazehai() {
+            yaku_vec.push(Yaku::Shousuushii);
+        }
+
+        // Chuurenpoutou: 1112345678999 and any one hai of the same suit, without any kan.
+        if decomposer.fuuro.is_empty() && suit_number(&all_hai) == 1 && !all_hai[0].is_jihai() {
+            let count = |hai_vec: &Vec<Hai>| {
+                let mut count = [0u8; 10];
+                for hai in hai_vec {
+                    count[hai.number() as usize] += 1;
+                }
+                count
+            };
+            let pattern = [0, 3, 1, 1, 1, 1, 1, 1, 1, 3];
+            let agari_count = count(&all_hai);
+            if (1..=9).all(|num| agari_count[num] >= pattern[num]) {
+                let mut machi_hai = all_hai.clone();
+                if let Some(index) = machi_hai
+                    .iter()
+                    .position(|hai| *hai == decomposer.agari_hai)
+                {
+                    machi_hai.remove(index);
+                }
+                yaku_vec.push(if count(&machi_hai) == pattern {
+                    Yaku::JunseiChuurenpoutou
+                } else {
+                    Yaku::Chuurenpoutou
+                });
+            }
+        }
+
+        yaku_vec
+    }
+}
+
+/// Return all hai of a reading, sorted.
+fn all_hai(decomposer: &AgariDecomposer) -> Vec<Hai> {
+    let mut all_hai = vec![];
+    for toitsu in &decomposer.toitsu_vec {
+        all_hai.push(toitsu.0);
+        all_hai.push(toitsu.0);
+    }
+    for mentsu in decomposer.all_mentsu() {
+        all_hai.extend(mentsu.hai_vec());
+    }
+    all_hai.sort();
+    all_hai
+}
+
+/// Return how many kinds of manzu, pinzu and souzu are used.
+fn suit_number(all_hai: &[Hai]) -> usize {
+    let manzu = all_hai.iter().any(|hai| matches!(hai, Hai::Manzu(_)));
+    let pinzu = all_hai.iter().any(|hai| matches!(hai, Hai::Pinzu(_)));
+    let souzu = all_hai.iter().any(|hai| matches!(hai, Hai::Souzu(_)));
+    manzu as usize + pinzu as usize + souzu as usize
+}
+
+/// Return the number of concealed koutsu, ankan included. Koutsu completed by ron is not
+/// concealed.
+///
+/// # Japanese
+/// * ankou: 暗刻
+fn ankou_number(decomposer: &AgariDecomposer, condition: &AgariCondition) -> usize {
+    decomposer
+        .mentsu_vec
+        .iter()
+        .filter(|mentsu| match mentsu {
+            Mentsu::Koutsu(hai) => {
+                condition.tsumo
+                    || decomposer.machi != Machi::Shanpon
+                    || *hai != decomposer.agari_hai
+            }
+            Mentsu::Kantsu(_) => true,
+            _ => false,
+        })
+        .count()
+        + decomposer
+            .fuuro
+            .iter()
+            .filter(|fuuro| !fuuro.is_open())
+            .count()
+}
+
+fn kantsu_number(decomposer: &AgariDecomposer) -> usize {
+    decomposer
+        .all_mentsu()
+        .iter()
+        .filter(|mentsu| matches!(mentsu, Mentsu::Kantsu(_)))
+        .count()
+}
+
+impl std::fmt::Display for Yaku {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn jihai_name(hai: &Hai) -> &'static str {
+            match hai {
+                Hai::Jihai(1) => "東",
+                Hai::Jihai(2) => "南",
+                Hai::Jihai(3) => "西",
+                Hai::Jihai(4) => "北",
+                Hai::Jihai(5) => "白",
+                Hai::Jihai(6) => "發",
+                Hai::Jihai(7) => "中",
+                _ => "",
+            }
+        }
+
+        let name = match self {
+            Yaku::Riichi => "立直",
+            Yaku::DoubleRiichi => "両立直",
+            Yaku::Ippatsu => "一発",
+            Yaku::MenzenTsumo => "門前清自摸和",
+            Yaku::Tanyao => "断幺九",
+            Yaku::Pinfu => "平和",
+            Yaku::Iipeikou => "一盃口",
+            Yaku::Bakaze(hai) => return write!(f, "場風 {}", jihai_name(hai)),
+            Yaku::Jikaze(hai) => return write!(f, "自風 {}", jihai_name(hai)),
+            Yaku::Sangenpai(hai) => return write!(f, "役牌 {}", jihai_name(hai)),
+            Yaku::Haitei => "海底摸月",
+            Yaku::Houtei => "河底撈魚",
+            Yaku::Rinshan => "嶺上開花",
+            Yaku::Chankan => "槍槓",
+            Yaku::Chiitoitsu => "七対子",
+            Yaku::Sanshokudoujun => "三色同順",
+            Yaku::Ittsu => "一気通貫",
+            Yaku::Chanta => "混全帯幺九",
+            Yaku::Toitoi => "対々和",
+            Yaku::Sanankou => "三暗刻",
+            Yaku::Sanshokudoukou => "三色同刻",
+            Yaku::Sankantsu => "三槓子",
+            Yaku::Shousangen => "小三元",
+            Yaku::Honroutou => "混老頭",
+            Yaku::Ryanpeikou => "二盃口",
+            Yaku::Junchan => "純全帯幺九",
+            Yaku::Honitsu => "混一色",
+            Yaku::Chinitsu => "清一色",
+            Yaku::Kokushimusou => "国士無双",
+            Yaku::KokushimusouJuusanmen => "国士無双十三面",
+            Yaku::Suuankou => "四暗刻",
+            Yaku::SuuankouTanki => "四暗刻単騎",
+            Yaku::Daisangen => "大三元",
+            Yaku::Shousuushii => "小四喜",
+            Yaku::Daisuushii => "大四喜",
+            Yaku::Tsuuiisou => "字一色",
+            Yaku::Ryuuiisou => "緑一色",
+            Yaku::Chinroutou => "清老頭",
+            Yaku::Chuurenpoutou => "九蓮宝燈",
+            Yaku::JunseiChuurenpoutou => "純正九蓮宝燈",
+            Yaku::Suukantsu => "四槓子",
+            Yaku::Tenhou => "天和",
+            Yaku::Chiihou => "地和",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::Tehai;
+
+    /// Return yaku of every reading of the tehai.
+    fn judge(tehai: &str, condition: &AgariCondition) -> Vec<Vec<Yaku>> {
+        Tehai::new(tehai.to_string())
+            .unwrap()
+            .agari_decompose(condition.agari_hai)
+            .unwrap()
+            .iter()
+            .map(|decomposer| Yaku::judge(decomposer, condition))
+            .collect()
+    }
+
+    #[test]
+    fn pinfu_reading() {
+        // 567m completed on kanchan or 678m on ryanmen.
+        let condition = AgariCondition::new(Hai::Manzu(6), false);
+        let mut found = judge("566778m234p456s99s", &condition);
+        found.sort();
+        assert_eq!(found, [vec![], vec![Yaku::Pinfu]]);
+
+        let condition = AgariCondition::new(Hai::Manzu(6), true);
+        let mut found = judge("566778m234p456s99s", &condition);
+        found.sort();
+        assert_eq!(
+            found,
+            [
+                vec![Yaku::MenzenTsumo],
+                vec![Yaku::MenzenTsumo, Yaku::Pinfu]
+            ]
+        );
+
+        // Yakuhai jantou.
+        let condition = AgariCondition::new(Hai::Manzu(6), false);
+        assert!(judge("566778m234p456s77z", &condition)
+            .iter()
+            .all(|yaku_vec| !yaku_vec.contains(&Yaku::Pinfu)));
+    }
+
+    #[test]
+    fn sanankou() {
+        let condition = AgariCondition::new(Hai::Manzu(5), false);
+        assert_eq!(
+            judge("111m222p333s44m555m", &condition),
+            [vec![Yaku::Toitoi, Yaku::Sanankou]]
+        );
+        assert_eq!(
+            judge("111m222p345s44m555m", &condition),
+            [Vec::<Yaku>::new()]
+        );
+
+        let condition = AgariCondition::new(Hai::Manzu(5), true);
+        assert_eq!(
+            judge("111m222p333s44m555m", &condition),
+            [vec![Yaku::Suuankou]]
+        );
+        assert_eq!(
+            judge("111m222p345s44m555m", &condition),
+            [vec![Yaku::MenzenTsumo, Yaku::Sanankou]]
+        );
+    }
+
+    #[test]
+    fn chanta_and_junchan() {
+        let condition = AgariCondition::new(Hai::Jihai(3), false);
+        assert_eq!(
+            judge("123m789m123p999s33z", &condition),
+            [vec![Yaku::Chanta]]
+        );
+        let condition = AgariCondition::new(Hai::Manzu(8), false);
+        assert_eq!(
+            judge("123m789m123p999s11p", &condition),
+            [vec![Yaku::Junchan]]
+        );
+        assert_eq!(
+            judge("789m123p999s11p[123m]", &condition),
+            [vec![Yaku::Junchan]]
+        );
+        assert_eq!(Yaku::Chanta.han(true), 2);
+        assert_eq!(Yaku::Chanta.han(false), 1);
+        assert_eq!(Yaku::Junchan.han(true), 3);
+        assert_eq!(Yaku::Junchan.han(false), 2);
+
+        // 11z makes honroutou when all hai are yaochuupai.
+        let condition = AgariCondition::new(Hai::Manzu(9), false);
+        assert_eq!(
+            judge("111m999m111p999s33z", &condition),
+            [vec![Yaku::Toitoi, Yaku::Sanankou, Yaku::Honroutou]]
+        );
+    }
+
+    #[test]
+    fn ryanpeikou_and_chiitoitsu() {
+        let condition = AgariCondition::new(Hai::Souzu(7), false);
+        let mut found = judge("112233m445566p77s", &condition);
+        found.sort();
+        assert_eq!(found, [vec![Yaku::Chiitoitsu], vec![Yaku::Ryanpeikou]]);
+        assert_eq!(
+            judge("112233m445599p77s", &condition),
+            [vec![Yaku::Chiitoitsu]]
+        );
+    }
+
+    #[test]
+    fn yakuman() {
+        let ron = |hai| AgariCondition::new(hai, false);
+        assert_eq!(
+            judge("123m55p555666777z", &ron(Hai::Jihai(7))),
+            [vec![Yaku::Daisangen]]
+        );
+        assert_eq!(
+            judge("19m19p19s12345677z", &ron(Hai::Jihai(6))),
+            [vec![Yaku::Kokushimusou]]
+        );
+        assert_eq!(
+            judge("19m19p19s12345677z", &ron(Hai::Jihai(7))),
+            [vec![Yaku::KokushimusouJuusanmen]]
+        );
+        assert_eq!(
+            judge("111m222p333s444s55z", &ron(Hai::Jihai(5))),
+            [vec![Yaku::SuuankouTanki]]
+        );
+        assert_eq!(
+            judge("11122233344455z", &ron(Hai::Jihai(5))),
+            [vec![Yaku::SuuankouTanki, Yaku::Daisuushii, Yaku::Tsuuiisou]]
+        );
+        assert_eq!(
+            judge("22334466888s666z", &ron(Hai::Souzu(8))),
+            [vec![Yaku::Ryuuiisou]]
+        );
+        assert_eq!(
+            judge("111m999m111p999p11s", &ron(Hai::Souzu(1))),
+            [vec![Yaku::SuuankouTanki, Yaku::Chinroutou]]
+        );
+
+        let mut condition = ron(Hai::Pinzu(1));
+        condition.tsumo = true;
+        condition.tenhou = true;
+        condition.jikaze = Hai::Jihai(1);
+        assert_eq!(
+            judge("123m456m789m123s11p", &condition),
+            [vec![Yaku::Tenhou]]
+        );
+        condition.jikaze = Hai::Jihai(2);
+        assert_eq!(
+            judge("123m456m789m123s11p", &condition),
+            [vec![Yaku::Chiihou]]
+        );
+    }
+
+    #[test]
+    fn chuurenpoutou() {
+        let condition = AgariCondition::new(Hai::Manzu(2), false);
+        assert!(judge("11123455678999m", &condition)
+            .iter()
+            .all(|yaku_vec| yaku_vec == &[Yaku::Chuurenpoutou]));
+        let condition = AgariCondition::new(Hai::Manzu(5), false);
+        assert!(judge("11123455678999m", &condition)
+            .iter()
+            .all(|yaku_vec| yaku_vec == &[Yaku::JunseiChuurenpoutou]));
+
+        // Kan tiles are not a part of the pattern.
+        for yaku_vec in judge("23455678999m(1111m)", &condition) {
+            assert!(!yaku_vec.iter().any(|yaku| yaku.is_yakuman()));
+        }
+    }
+}
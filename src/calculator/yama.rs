@@ -0,0 +1,286 @@
+use super::Hai;
+
+/// Deterministic random number generator, SplitMix64, so the same seed always gives the same
+/// yama on any platform.
+#[derive(Clone, Debug)]
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Return a number in `0..bound` without bias.
+    fn below(&mut self, bound: u64) -> u64 {
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let product = self.next() as u128 * bound as u128;
+            if product as u64 >= threshold {
+                return (product >> 64) as u64;
+            }
+        }
+    }
+}
+
+/// All hai of a game, shuffled from a seed.
+///
+/// # Japanese
+/// * Yama: 山
+/// * wanpai: 王牌
+/// * haipai: 配牌
+/// * tsumo: 自摸
+/// * rinshanpai: 嶺上牌
+/// * kandora: 槓ドラ
+/// * haitei: 海底
+///
+/// # Layout
+/// Hai are drawn from the front. The last 14 hai are wanpai, the first 4 of which are
+/// rinshanpai, followed by 5 pairs of dora indicator and uradora indicator. Each rinshanpai
+/// drawn takes the last hai left to draw into wanpai, which is drawn as rinshanpai after
+/// the first 4, so wanpai always has 14 hai.
+///
+/// There are 136 hai for 4 players and 108 for 3 players without 2\~8m, each with one red
+/// five of every suit instead of a normal five.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Yama {
+    hai_vec: Vec<Hai>,
+    player_number: u8,
+    tsumo_number: usize,
+    rinshan_number: usize,
+    kandora_number: usize,
+}
+
+impl Yama {
+    /// Number of hai in wanpai.
+    pub const WANPAI_NUMBER: usize = 14;
+    /// Number of rinshanpai at the start of wanpai.
+    pub const RINSHANPAI_NUMBER: usize = 4;
+    /// Most number of kandora, one for each kan.
+    pub const MAX_KANDORA_NUMBER: usize = 4;
+
+    /// Create yama shuffled from `seed`.
+    ///
+    /// # Parameters
+    /// * player_number: Number of players, 3 or 4.
+    /// * seed: Any number. The same seed always makes the same yama.
+    pub fn new(player_number: u8, seed: u64) -> Self {
+        let mut hai_vec = vec![];
+        for hai in Hai::all_type(player_number) {
+            let aka = match hai {
+                Hai::Manzu(5) => Hai::Manzu(0),
+                Hai::Pinzu(5) => Hai::Pinzu(0),
+                Hai::Souzu(5) => Hai::Souzu(0),
+                _ => hai,
+            };
+            if aka != hai && aka.is_valid(player_number) {
+                hai_vec.extend([aka, hai, hai, hai]);
+            } else {
+                hai_vec.extend([hai; 4]);
+            }
+        }
+
+        // Fisher-Yates shuffle.
+        let mut rng = Rng(seed);
+        for index in (1..hai_vec.len()).rev() {
+            hai_vec.swap(index, rng.below(index as u64 + 1) as usize);
+        }
+
+        Self {
+            hai_vec,
+            player_number,
+            tsumo_number: 0,
+            rinshan_number: 0,
+            kandora_number: 0,
+        }
+    }
+
+    /// Return all hai in the order of drawing, wanpai at the end.
+    pub fn hai_vec(&self) -> &[Hai] {
+        &self.hai_vec
+    }
+
+    /// Deal 13 hai to each player, 4 at a time for 3 rounds and then 1 each, starting from
+    /// oya. Each tehai is sorted, oya first.
+    pub fn haipai(&mut self) -> Vec<Vec<Hai>> {
+        let player_number = self.player_number as usize;
+        let mut haipai = vec![vec![]; player_number];
+        for number in [4, 4, 4, 1] {
+            for juntehai in haipai.iter_mut() {
+                for _ in 0..number {
+                    juntehai.extend(self.tsumo());
+                }
+            }
+        }
+        for juntehai in haipai.iter_mut() {
+            juntehai.sort();
+        }
+        haipai
+    }
+
+    /// Draw the next hai, or `None` if nothing left to draw.
+    pub fn tsumo(&mut self) -> Option<Hai> {
+        if self.nokori() == 0 {
+            return None;
+        }
+        self.tsumo_number += 1;
+        Some(self.hai_vec[self.tsumo_number - 1])
+    }
+
+    /// Draw rinshanpai after kan or nukidora, or `None` if nothing left to draw, when kan
+    /// is not allowed either.
+    pub fn rinshan(&mut self) -> Option<Hai> {
+        if self.nokori() == 0 {
+            return None;
+        }
+        let index = if self.rinshan_number < Self::RINSHANPAI_NUMBER {
+            self.wanpai_start() + self.rinshan_number
+        } else {
+            // The one moved into wanpai by the rinshanpai 4 draws before.
+            self.hai_vec.len()
+                - Self::WANPAI_NUMBER
+                - 1
+                - (self.rinshan_number - Self::RINSHANPAI_NUMBER)
+        };
+        self.rinshan_number += 1;
+        Some(self.hai_vec[index])
+    }
+
+    /// Reveal a kandora indicator after kan, returning it, or `None` if all revealed.
+    pub fn kandora(&mut self) -> Option<Hai> {
+        if self.kandora_number >= Self::MAX_KANDORA_NUMBER {
+            return None;
+        }
+        self.kandora_number += 1;
+        self.dora_hyouji().last().copied()
+    }
+
+    /// Return all revealed dora indicators, kandora included.
+    pub fn dora_hyouji(&self) -> Vec<Hai> {
+        (0..=self.kandora_number)
+            .map(|index| self.hai_vec[self.wanpai_start() + Self::RINSHANPAI_NUMBER + 2 * index])
+            .collect()
+    }
+
+    /// Return uradora indicators under all revealed dora indicators.
+    pub fn uradora_hyouji(&self) -> Vec<Hai> {
+        (0..=self.kandora_number)
+            .map(|index| {
+                self.hai_vec[self.wanpai_start() + Self::RINSHANPAI_NUMBER + 2 * index + 1]
+            })
+            .collect()
+    }
+
+    /// Return the number of hai left to draw, wanpai excluded.
+    pub fn nokori(&self) -> usize {
+        self.hai_vec.len() - Self::WANPAI_NUMBER - self.tsumo_number - self.rinshan_number
+    }
+
+    /// Return if nothing left to draw, so the last tsumo was haitei and the next sutehai is
+    /// houtei.
+    pub fn is_haitei(&self) -> bool {
+        self.nokori() == 0
+    }
+
+    /// Return the number of players, 3 or 4.
+    pub fn player_number(&self) -> u8 {
+        self.player_number
+    }
+
+    fn wanpai_start(&self) -> usize {
+        self.hai_vec.len() - Self::WANPAI_NUMBER
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::HaiCounts;
+
+    #[test]
+    fn all_hai() {
+        for (player_number, total, aka) in [(4, 136, 3), (3, 108, 2)] {
+            let yama = Yama::new(player_number, 1);
+            assert_eq!(yama.hai_vec().len(), total);
+            assert_eq!(
+                yama.hai_vec().iter().filter(|hai| hai.is_aka()).count(),
+                aka
+            );
+            let counts = HaiCounts::from(yama.hai_vec()).normalized();
+            for hai in Hai::all_type(player_number) {
+                assert_eq!(counts[hai.index()], 4, "{}", hai);
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_yama() {
+        assert_eq!(Yama::new(4, 20190622), Yama::new(4, 20190622));
+        assert_ne!(Yama::new(4, 20190622), Yama::new(4, 20190623));
+        assert_eq!(
+            Yama::new(4, 0).hai_vec()[..8],
+            [
+                Hai::Pinzu(0),
+                Hai::Souzu(9),
+                Hai::Manzu(4),
+                Hai::Manzu(7),
+                Hai::Pinzu(9),
+                Hai::Souzu(3),
+                Hai::Manzu(8),
+                Hai::Manzu(9),
+            ]
+        );
+    }
+
+    #[test]
+    fn haipai_and_tsumo() {
+        let mut yama = Yama::new(4, 7);
+        let hai_vec = yama.hai_vec().to_vec();
+        let haipai = yama.haipai();
+        assert_eq!(haipai.len(), 4);
+        assert!(haipai.iter().all(|juntehai| juntehai.len() == 13));
+        let mut oya = hai_vec[0..4].to_vec();
+        oya.extend(&hai_vec[16..20]);
+        oya.extend(&hai_vec[32..36]);
+        oya.push(hai_vec[48]);
+        oya.sort();
+        assert_eq!(haipai[0], oya);
+
+        assert_eq!(yama.nokori(), 136 - 14 - 52);
+        assert_eq!(yama.tsumo(), Some(hai_vec[52]));
+        while yama.nokori() > 1 {
+            yama.tsumo();
+        }
+        assert!(!yama.is_haitei());
+        assert_eq!(yama.tsumo(), Some(hai_vec[121]));
+        assert!(yama.is_haitei());
+        assert_eq!(yama.tsumo(), None);
+        assert_eq!(yama.rinshan(), None);
+    }
+
+    #[test]
+    fn rinshan_and_kandora() {
+        let mut yama = Yama::new(4, 7);
+        let hai_vec = yama.hai_vec().to_vec();
+        assert_eq!(yama.dora_hyouji(), [hai_vec[126]]);
+        assert_eq!(yama.uradora_hyouji(), [hai_vec[127]]);
+
+        for index in 0..4 {
+            assert_eq!(yama.rinshan(), Some(hai_vec[122 + index]));
+            assert_eq!(yama.kandora(), Some(hai_vec[128 + 2 * index]));
+        }
+        assert_eq!(yama.kandora(), None);
+        assert_eq!(yama.dora_hyouji().len(), 5);
+        assert_eq!(yama.uradora_hyouji()[4], hai_vec[135]);
+        assert_eq!(yama.nokori(), 122 - 4);
+
+        // Then the hai moved into wanpai from the end.
+        assert_eq!(yama.rinshan(), Some(hai_vec[121]));
+        assert_eq!(yama.rinshan(), Some(hai_vec[120]));
+        assert_eq!(yama.tsumo(), Some(hai_vec[0]));
+        assert_eq!(yama.nokori(), 122 - 7);
+    }
+}
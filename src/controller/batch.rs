@@ -0,0 +1,171 @@
+use super::{json, Controller, OutputFormat};
+use crate::calculator;
+use crate::calculator::StyledDisplay;
+use std::io::{BufRead, Write};
+
+/// Result of a batch.
+///
+/// # Member
+/// * total: the number of tehai read, blank lines excluded.
+/// * analyzed: the number of tehai analyzed.
+/// * failed: the number of lines with any error.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub analyzed: usize,
+    pub failed: usize,
+}
+
+impl Controller {
+    /// Analyze a tehai on each line of `input`, writing one line of result for each.
+    ///
+    /// Blank lines are skipped, and lines with any error are reported and then skipped.
+    /// A summary is written after all lines. Only io errors stop the batch.
+    ///
+    /// Standard output is like `3\t1m2m3m\t向聴：1\t打 9s 摸 1m  共4枚 | 打 1m ...`, with
+    /// the line number and the input. JSON output is like `{"line":3,"input":"123m",
+    /// "result":{...}}`, or with `"error"` instead of `"result"`.
+    pub fn batch<R: BufRead, W: Write>(
+        &self,
+        input: R,
+        output: &mut W,
+    ) -> std::io::Result<BatchSummary> {
+        let mut summary = BatchSummary::default();
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            summary.total += 1;
+
+            let result = self.batch_line(line);
+            if result.is_ok() {
+                summary.analyzed += 1;
+            } else {
+                summary.failed += 1;
+            }
+            writeln!(
+                output,
+                "{}",
+                match (self.output_format(), result) {
+                    (OutputFormat::Standard, Ok(result)) => {
+                        format!("{}\t{}\t{}", index + 1, line, result)
+                    }
+                    (OutputFormat::Standard, Err(error)) => {
+                        format!("{}\t{}\terror: {}", index + 1, line, error)
+                    }
+                    (OutputFormat::Json, result) => json::batch_line(index + 1, line, &result),
+                }
+            )?;
+        }
+
+        writeln!(
+            output,
+            "{}",
+            match self.output_format() {
+                OutputFormat::Standard => format!(
+                    "{} lines, {} analyzed, {} failed",
+                    summary.total, summary.analyzed, summary.failed
+                ),
+                OutputFormat::Json => json::batch_summary(&summary),
+            }
+        )?;
+        Ok(summary)
+    }
+
+    /// Analyze one line, into a line for standard output or a JSON object.
+    fn batch_line(&self, line: &str) -> Result<String, calculator::Error> {
+        let tehai = calculator::Tehai::with_player_number(line.to_string(), self.player_number())?;
+        let taku = self.taku()?;
+        let (shanten, conditions) = tehai.analyze(&taku)?;
+
+        Ok(match self.output_format() {
+            OutputFormat::Standard => {
+                let shanten_string = match shanten {
+                    -1 => "和了".to_string(),
+                    0 => "聴牌".to_string(),
+                    shanten => format!("向聴：{}", shanten),
+                };
+                let conditions_string: Vec<String> = conditions
+                    .iter()
+                    .map(|condition| condition.styled(self.hai_style()).to_string())
+                    .collect();
+                if conditions_string.is_empty() {
+                    shanten_string
+                } else {
+                    format!("{}\t{}", shanten_string, conditions_string.join(" | "))
+                }
+            }
+            OutputFormat::Json => json::machi(&tehai, &taku, shanten, &conditions),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Blank lines, an error on the first line, then good lines.
+    const INPUT: &str = "1x\n\n  \n12m[111z]\n11m\n";
+
+    fn batch(output_format: OutputFormat) -> (BatchSummary, Vec<String>) {
+        let mut output = vec![];
+        let summary = Controller::new(output_format, 4)
+            .batch(Cursor::new(INPUT), &mut output)
+            .unwrap();
+        let lines = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+        (summary, lines)
+    }
+
+    #[test]
+    fn standard() {
+        let (summary, lines) = batch(OutputFormat::Standard);
+        let expected = BatchSummary {
+            total: 3,
+            analyzed: 2,
+            failed: 1,
+        };
+        assert_eq!(summary, expected);
+        assert_eq!(
+            lines,
+            [
+                "1\t1x\terror: Unknown character 'x' at index 1.",
+                "4\t12m[111z]\t聴牌\t打 1m 摸 2m  共3枚 | 打 2m 摸 1m  共3枚",
+                "5\t11m\t和了",
+                "3 lines, 2 analyzed, 1 failed",
+            ]
+        );
+    }
+
+    #[test]
+    fn json() {
+        let (summary, lines) = batch(OutputFormat::Json);
+        let expected = BatchSummary {
+            total: 3,
+            analyzed: 2,
+            failed: 1,
+        };
+        assert_eq!(summary, expected);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            concat!(
+                r#"{"line":1,"input":"1x","#,
+                r#""error":{"error":"Unknown character 'x' at index 1.","#,
+                r#""kind":"UnknownCharacter","index":1}}"#
+            )
+        );
+        assert!(lines[1].starts_with(r#"{"line":4,"input":"12m[111z]","result":{"#));
+        assert!(lines[2].starts_with(r#"{"line":5,"input":"11m","result":{"#));
+        assert_eq!(
+            lines[3],
+            r#"{"summary":{"total":3,"analyzed":2,"failed":1}}"#
+        );
+    }
+}
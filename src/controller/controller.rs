@@ -1,26 +1,252 @@
+use super::json;
 use crate::calculator;
+use crate::calculator::StyledDisplay;
 use std::io::{stdout, Write};
 
 #[derive(Clone, Debug)]
 pub struct Controller {
     output_format: OutputFormat,
+    player_number: u8,
+    dora_hyouji: String,
+    uradora_hyouji: String,
+    kawa: String,
+    tacha_kawa: String,
+    kairyou: bool,
+    agari_hai: Option<calculator::Hai>,
+    tsumo: bool,
+    riichi: bool,
+    honba: u8,
+    kyoutaku: u8,
+    bakaze: calculator::Hai,
+    jikaze: calculator::Hai,
+    hai_style: calculator::HaiStyle,
 }
 
 #[derive(Copy, Clone, Debug)]
 pub enum OutputFormat {
-    Standard
+    Standard,
+    Json,
 }
 
+/// Error of command line arguments.
+///
+/// # Variant
+/// * UnknownFlag: argument starting with `-` but not supported, like `--halp`.
+/// * InvalidValue: value of the flag cannot be parsed, like `--honba=x` or `--jikaze=5z`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgsError {
+    UnknownFlag(String),
+    InvalidValue(String),
+}
+
+impl std::fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgsError::UnknownFlag(flag) => {
+                write!(f, "Unknown option '{}', see --help for usage.", flag)
+            }
+            ArgsError::InvalidValue(flag) => write!(f, "Invalid value of option '{}'.", flag),
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
 impl Controller {
     pub fn new(
         output_format: OutputFormat,
+        player_number: u8,
     ) -> Self {
         stdout().flush().unwrap();
         
 
         Self {
             output_format,
+            player_number,
+            dora_hyouji: String::new(),
+            uradora_hyouji: String::new(),
+            kawa: String::new(),
+            tacha_kawa: String::new(),
+            kairyou: false,
+            agari_hai: None,
+            tsumo: false,
+            riichi: false,
+            honba: 0,
+            kyoutaku: 0,
+            bakaze: calculator::Hai::Jihai(1),
+            jikaze: calculator::Hai::Jihai(2),
+            hai_style: calculator::HaiStyle::Text,
+        }
+    }
+
+    /// Create controller from command line arguments, returning it with the tehai string.
+    ///
+    /// `--sanma` for 3-players mode, `--dora=1z3p` and `--ura=4s` for indicators,
+    /// `--kawa=19m5z` for own sutehai, `--tacha=1z4p4p` for other visible hai, `--kairyou`
+    /// for kairyouhai, `--unicode` or `--color` for printing hai as mahjong tiles or in colors,
+    /// and `--json` for JSON output. The first argument not starting with `-` is the tehai.
+    ///
+    /// Points of a complete tehai are decided by `--agari=5m`, `--tsumo`, `--riichi`,
+    /// `--honba=1`, `--kyoutaku=1`, `--bakaze=2z` and `--jikaze=1z`.
+    /// `--batch` and `--batch=<path>` are accepted but handled by the binary.
+    pub fn from_args(args: &[String]) -> Result<(Self, String), ArgsError> {
+        let player_number = if args.iter().any(|arg| arg == "--sanma") {
+            3
+        } else {
+            4
+        };
+        let output_format = if args.iter().any(|arg| arg == "--json") {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Standard
+        };
+        let mut controller = Self::new(output_format, player_number);
+        controller.set_kairyou(args.iter().any(|arg| arg == "--kairyou"));
+        controller.set_tsumo(args.iter().any(|arg| arg == "--tsumo"));
+        controller.set_riichi(args.iter().any(|arg| arg == "--riichi"));
+        if args.iter().any(|arg| arg == "--unicode") {
+            controller.set_hai_style(calculator::HaiStyle::Unicode);
+        } else if args.iter().any(|arg| arg == "--color") {
+            controller.set_hai_style(calculator::HaiStyle::Color);
         }
+
+        let invalid = |arg: &String| ArgsError::InvalidValue(arg.clone());
+        let parse_hai = |arg: &String, value: &str| {
+            let hai_vec =
+                calculator::Taku::parse_hai_vec(value, player_number).map_err(|_| invalid(arg))?;
+            match hai_vec[..] {
+                [hai] => Ok(hai),
+                _ => Err(invalid(arg)),
+            }
+        };
+        let parse_kaze = |arg: &String, value: &str| match parse_hai(arg, value)? {
+            kaze @ calculator::Hai::Jihai(1..=4) => Ok(kaze),
+            _ => Err(invalid(arg)),
+        };
+
+        for arg in args {
+            if let Some(agari_hai) = arg.strip_prefix("--agari=") {
+                controller.set_agari_hai(Some(parse_hai(arg, agari_hai)?));
+            } else if let Some(honba) = arg.strip_prefix("--honba=") {
+                controller.set_honba(honba.parse().map_err(|_| invalid(arg))?);
+            } else if let Some(kyoutaku) = arg.strip_prefix("--kyoutaku=") {
+                controller.set_kyoutaku(kyoutaku.parse().map_err(|_| invalid(arg))?);
+            } else if let Some(bakaze) = arg.strip_prefix("--bakaze=") {
+                controller.set_bakaze(parse_kaze(arg, bakaze)?);
+            } else if let Some(jikaze) = arg.strip_prefix("--jikaze=") {
+                controller.set_jikaze(parse_kaze(arg, jikaze)?);
+            } else if let Some(dora_hyouji) = arg.strip_prefix("--dora=") {
+                controller.set_dora_hyouji(dora_hyouji.to_string());
+            } else if let Some(uradora_hyouji) = arg.strip_prefix("--ura=") {
+                controller.set_uradora_hyouji(uradora_hyouji.to_string());
+            } else if let Some(kawa) = arg.strip_prefix("--kawa=") {
+                controller.set_kawa(kawa.to_string());
+            } else if let Some(tacha_kawa) = arg.strip_prefix("--tacha=") {
+                controller.set_tacha_kawa(tacha_kawa.to_string());
+            } else if arg.starts_with('-')
+                && !matches!(
+                    arg.as_str(),
+                    "--sanma"
+                        | "--json"
+                        | "--kairyou"
+                        | "--unicode"
+                        | "--color"
+                        | "--batch"
+                        | "--tsumo"
+                        | "--riichi"
+                )
+                && !arg.starts_with("--batch=")
+            {
+                return Err(ArgsError::UnknownFlag(arg.clone()));
+            }
+        }
+
+        let command = args
+            .iter()
+            .find(|arg| !arg.starts_with('-'))
+            .cloned()
+            .unwrap_or_default();
+        Ok((controller, command))
+    }
+
+    /// Set dora indicators, like `1z3p`.
+    pub fn set_dora_hyouji(&mut self, dora_hyouji: String) {
+        self.dora_hyouji = dora_hyouji;
+    }
+
+    /// Set uradora indicators, like `4s`.
+    pub fn set_uradora_hyouji(&mut self, uradora_hyouji: String) {
+        self.uradora_hyouji = uradora_hyouji;
+    }
+
+    /// Set sutehai of the player, like `19m5z`.
+    pub fn set_kawa(&mut self, kawa: String) {
+        self.kawa = kawa;
+    }
+
+    /// Set sutehai of other players and anything else visible, like `1z4p4p`.
+    pub fn set_tacha_kawa(&mut self, tacha_kawa: String) {
+        self.tacha_kawa = tacha_kawa;
+    }
+
+    /// Set the hai completing the tehai. If `None`, the one with most points is taken.
+    pub fn set_agari_hai(&mut self, agari_hai: Option<calculator::Hai>) {
+        self.agari_hai = agari_hai;
+    }
+
+    /// Set if winning by tsumo, otherwise by ron.
+    pub fn set_tsumo(&mut self, tsumo: bool) {
+        self.tsumo = tsumo;
+    }
+
+    /// Set if riichi declared, which also counts uradora.
+    pub fn set_riichi(&mut self, riichi: bool) {
+        self.riichi = riichi;
+    }
+
+    /// Set number of honba sticks on the table.
+    pub fn set_honba(&mut self, honba: u8) {
+        self.honba = honba;
+    }
+
+    /// Set number of riichi sticks on the table.
+    pub fn set_kyoutaku(&mut self, kyoutaku: u8) {
+        self.kyoutaku = kyoutaku;
+    }
+
+    /// Set wind of the round, 1\~4z. 1z by default.
+    pub fn set_bakaze(&mut self, bakaze: calculator::Hai) {
+        self.bakaze = bakaze;
+    }
+
+    /// Set wind of the player, 1\~4z. 2z by default, so the player is ko.
+    pub fn set_jikaze(&mut self, jikaze: calculator::Hai) {
+        self.jikaze = jikaze;
+    }
+
+    /// Return wind of the player.
+    pub fn jikaze(&self) -> calculator::Hai {
+        self.jikaze
+    }
+
+    /// Return number of players, 3 or 4.
+    pub fn player_number(&self) -> u8 {
+        self.player_number
+    }
+
+    /// Set if kairyouhai are analyzed for each sutehai, which takes much more time.
+    pub fn set_kairyou(&mut self, kairyou: bool) {
+        self.kairyou = kairyou;
+    }
+
+    /// Set how hai are printed in standard output. JSON output is not affected.
+    pub fn set_hai_style(&mut self, hai_style: calculator::HaiStyle) {
+        self.hai_style = hai_style;
+    }
+
+    /// Return how hai are printed in standard output.
+    pub fn hai_style(&self) -> calculator::HaiStyle {
+        self.hai_style
     }
 
     pub fn execute(&mut self, command: String) -> String {
@@ -30,26 +256,63 @@ impl Controller {
             Ok(Some(output)) => {
                 output
             }
-            Err(error) => match self.output_format {
-                OutputFormat::Standard => {
-                    error
-                }
-            },
+            Err(error) => self.error_string(&error),
             _ => String::new(),
         }
     }
 
-    fn execute_core(&mut self, command: String) -> Result<Option<String>, String> {
-        fn print_machi(
+    /// Return the output format.
+    pub fn output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
+    /// Parse hai on taku set by setters.
+    pub(super) fn taku(&self) -> Result<calculator::Taku, calculator::Error> {
+        let parse_hai_vec =
+            |string: &String| calculator::Taku::parse_hai_vec(string, self.player_number);
+        Ok(calculator::Taku {
+            dora_hyouji: parse_hai_vec(&self.dora_hyouji)?,
+            uradora_hyouji: parse_hai_vec(&self.uradora_hyouji)?,
+            kawa: parse_hai_vec(&self.kawa)?,
+            tacha_kawa: parse_hai_vec(&self.tacha_kawa)?,
+        })
+    }
+
+    /// Print an error in the output format.
+    pub(super) fn error_string(&self, error: &calculator::Error) -> String {
+        match self.output_format {
+            OutputFormat::Standard => error.to_string(),
+            OutputFormat::Json => json::error(error),
+        }
+    }
+
+    fn execute_core(&mut self, command: String) -> Result<Option<String>, calculator::Error> {
+        fn print_machi<T: std::fmt::Display>(
             tehai: &calculator::Tehai,
+            taku: &calculator::Taku,
             shanten: i32,
-            conditions: Vec<calculator::MachiCondition>,
+            conditions: Vec<T>,
+            style: calculator::HaiStyle,
         ) -> String {
+            let mut dora_string = String::new();
+            if !taku.dora_hyouji.is_empty() {
+                dora_string += &format!("ドラ：{}\n", tehai.dora(&taku.dora_hyouji));
+            }
+            if !taku.uradora_hyouji.is_empty() {
+                dora_string += &format!("裏ドラ：{}\n", tehai.dora(&taku.uradora_hyouji));
+            }
+
             format!(
-                "手牌：{}\n{}",
-                tehai,
+                "手牌：{}\n{}{}{}",
+                tehai.styled(style),
+                dora_string,
+                if tehai.aka() > 0 {
+                    format!("赤ドラ：{}\n", tehai.aka())
+                } else {
+                    String::new()
+                },
                 if shanten == -1 {
-                    format!("和了")
+                    "和了".to_string()
                 } else {
                     let mut conditions_string = String::new();
                     for i in conditions {
@@ -58,7 +321,7 @@ impl Controller {
                     format!(
                         "{}\n--------{}",
                         if shanten == 0 {
-                            format!("聴牌")
+                            "聴牌".to_string()
                         } else {
                             format!("向聴：{}", shanten)
                         },
@@ -68,16 +331,251 @@ impl Controller {
             )
         }
 
-        let tehai = calculator::Tehai::new(
+        // If agari hai is unknown, take the one with most points.
+        fn best_tensuu(
+            controller: &Controller,
+            tehai: &calculator::Tehai,
+            taku: &calculator::Taku,
+        ) -> Result<Option<(calculator::Hai, calculator::Tensuu)>, calculator::Error> {
+            let mut agari_hai_vec = match controller.agari_hai {
+                Some(agari_hai) => vec![agari_hai],
+                None => tehai.juntehai.clone(),
+            };
+            agari_hai_vec.dedup();
+            let mut best: Option<(calculator::Hai, calculator::Tensuu)> = None;
+            for agari_hai in agari_hai_vec {
+                let mut condition = calculator::AgariCondition::new(agari_hai, controller.tsumo);
+                condition.riichi = controller.riichi;
+                condition.bakaze = controller.bakaze;
+                condition.jikaze = controller.jikaze;
+                condition.dora_hyouji = taku.dora_hyouji.clone();
+                condition.uradora_hyouji = taku.uradora_hyouji.clone();
+                condition.honba = controller.honba;
+                condition.kyoutaku = controller.kyoutaku;
+                if let Some(tensuu) = tehai.tensuu(&condition)? {
+                    if best
+                        .as_ref()
+                        .is_none_or(|(_, best_tensuu)| tensuu.total() > best_tensuu.total())
+                    {
+                        best = Some((agari_hai, tensuu));
+                    }
+                }
+            }
+
+            Ok(best)
+        }
+
+        fn print_tensuu(
+            best: &Option<(calculator::Hai, calculator::Tensuu)>,
+            tsumo: bool,
+            guessed: bool,
+            style: calculator::HaiStyle,
+        ) -> String {
+            let guessed = if guessed {
+                "（和了牌未指定、最高点）"
+            } else {
+                ""
+            };
+            match best {
+                Some((agari_hai, tensuu)) => format!(
+                    "\n--------\n{} {}{}\n{}\n{}",
+                    if tsumo { "ツモ" } else { "ロン" },
+                    agari_hai.styled(style),
+                    guessed,
+                    tensuu.decomposer.styled(style),
+                    tensuu
+                ),
+                None => "\n--------\n役なし".to_string(),
+            }
+        }
+
+        let tehai = calculator::Tehai::with_player_number(
             command,
+            self.player_number,
         )?;
 
-        let (shanten, conditions) = tehai.analyze()?;
-        return Ok(Some(print_machi(
-            &tehai,
-            shanten,
-            conditions,
-        )));
+        let taku = self.taku()?;
+        let style = self.hai_style;
+
+        // 3*k+1 tehai is waiting for drawing, no sutehai.
+        if tehai.juntehai.len() % 3 == 1 && self.kairyou {
+            let kairyou = tehai.kairyou(&taku)?;
+            return Ok(Some(match self.output_format {
+                OutputFormat::Standard => print_machi(
+                    &tehai,
+                    &taku,
+                    kairyou.ukeire.shanten,
+                    vec![kairyou.styled(style)],
+                    style,
+                ),
+                OutputFormat::Json => json::kairyou(&tehai, &taku, &kairyou),
+            }));
+        }
+        if tehai.juntehai.len() % 3 == 1 {
+            let ukeire = tehai.ukeire(&taku)?;
+            return Ok(Some(match self.output_format {
+                OutputFormat::Standard => print_machi(
+                    &tehai,
+                    &taku,
+                    ukeire.shanten,
+                    vec![ukeire.styled(style)],
+                    style,
+                ),
+                OutputFormat::Json => json::ukeire(&tehai, &taku, &ukeire),
+            }));
+        }
+
+        let (shanten, conditions) = tehai.analyze(&taku)?;
+        if shanten == -1 {
+            let best = best_tensuu(self, &tehai, &taku)?;
+            let guessed = self.agari_hai.is_none();
+            return Ok(Some(match self.output_format {
+                OutputFormat::Standard => {
+                    print_machi(
+                        &tehai,
+                        &taku,
+                        shanten,
+                        conditions
+                            .iter()
+                            .map(|condition| condition.styled(style))
+                            .collect(),
+                        style,
+                    ) + &print_tensuu(&best, self.tsumo, guessed, style)
+                }
+                OutputFormat::Json => json::agari(&tehai, &taku, &best, self.tsumo, guessed),
+            }));
+        }
+        if self.kairyou {
+            let conditions = tehai.kairyou_conditions(&taku)?;
+            return Ok(Some(match self.output_format {
+                OutputFormat::Standard => print_machi(
+                    &tehai,
+                    &taku,
+                    shanten,
+                    conditions
+                        .iter()
+                        .map(|(sutehai, kairyou)| {
+                            format!("打 {} {}", sutehai.styled(style), kairyou.styled(style))
+                                .replace('\n', "\n    ")
+                        })
+                        .collect(),
+                    style,
+                ),
+                OutputFormat::Json => json::kairyou_conditions(&tehai, &taku, shanten, &conditions),
+            }));
+        }
+        Ok(Some(match self.output_format {
+            OutputFormat::Standard => print_machi(
+                &tehai,
+                &taku,
+                shanten,
+                conditions
+                    .iter()
+                    .map(|condition| condition.styled(style))
+                    .collect(),
+                style,
+            ),
+            OutputFormat::Json => json::machi(&tehai, &taku, shanten, &conditions),
+        }))
             
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_args(args: &[&str]) -> Result<(Controller, String), ArgsError> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        Controller::from_args(&args)
+    }
+
+    #[test]
+    fn options_from_args() {
+        let (controller, tehai) = from_args(&[
+            "--dora=1z3p",
+            "123m456p789s1122z",
+            "--sanma",
+            "--json",
+            "--ura=4s",
+            "--kawa=19m5z",
+            "--tacha=1z4p4p",
+        ])
+        .unwrap();
+        assert_eq!(tehai, "123m456p789s1122z");
+        assert_eq!(controller.player_number, 3);
+        assert!(matches!(controller.output_format, OutputFormat::Json));
+        assert_eq!(controller.dora_hyouji, "1z3p");
+        assert_eq!(controller.uradora_hyouji, "4s");
+        assert_eq!(controller.kawa, "19m5z");
+        assert_eq!(controller.tacha_kawa, "1z4p4p");
+
+        let (controller, tehai) = from_args(&[]).unwrap();
+        assert_eq!(tehai, "");
+        assert_eq!(controller.player_number, 4);
+        assert!(matches!(controller.output_format, OutputFormat::Standard));
+        assert!(controller.dora_hyouji.is_empty());
+
+        let (_, tehai) = from_args(&["--batch=hands.txt", "--color"]).unwrap();
+        assert_eq!(tehai, "");
+    }
+
+    #[test]
+    fn agari_condition_from_args() {
+        let (mut controller, tehai) = from_args(&[
+            "234m456p678s55s345m",
+            "--agari=5s",
+            "--tsumo",
+            "--riichi",
+            "--honba=2",
+            "--kyoutaku=1",
+            "--bakaze=S",
+            "--jikaze=1z",
+        ])
+        .unwrap();
+        assert_eq!(controller.agari_hai, Some(calculator::Hai::Souzu(5)));
+        assert!(controller.tsumo && controller.riichi);
+        assert_eq!((controller.honba, controller.kyoutaku), (2, 1));
+        assert_eq!(controller.bakaze, calculator::Hai::Jihai(2));
+        assert_eq!(controller.jikaze, calculator::Hai::Jihai(1));
+
+        // Oya tsumo of 30 fu 3 han with 2 honba and 1 kyoutaku.
+        let output = controller.execute(tehai.clone());
+        assert!(output.contains("ツモ 5s\n"), "{}", output);
+        assert!(output.contains("立直 1飜"), "{}", output);
+        assert!(output.contains("2200点オール 供託1000点"), "{}", output);
+
+        // Ron on the best agari hai by default.
+        let (mut controller, tehai) = from_args(&[&tehai]).unwrap();
+        let output = controller.execute(tehai.clone());
+        assert!(
+            output.contains("ロン 2m（和了牌未指定、最高点）"),
+            "{}",
+            output
+        );
+
+        for arg in [
+            "--agari=5",
+            "--agari=5s6s",
+            "--honba=-1",
+            "--kyoutaku=x",
+            "--bakaze=5z",
+            "--jikaze=1m",
+        ] {
+            assert_eq!(
+                from_args(&[&tehai, arg]).unwrap_err(),
+                ArgsError::InvalidValue(arg.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_flag() {
+        for flag in ["--help", "-h", "--sanma=3", "--dora"] {
+            assert_eq!(
+                from_args(&["123m456p789s1122z", flag]).unwrap_err(),
+                ArgsError::UnknownFlag(flag.to_string())
+            );
+        }
+    }
+}
@@ -0,0 +1,411 @@
+use crate::calculator;
+
+/// Quote and escape a string for JSON.
+fn string(value: &str) -> String {
+    let mut output = String::from("\"");
+    for chr in value.chars() {
+        match chr {
+            '"' => output += "\\\"",
+            '\\' => output += "\\\\",
+            '\n' => output += "\\n",
+            '\r' => output += "\\r",
+            '\t' => output += "\\t",
+            chr if (chr as u32) < 0x20 => output += &format!("\\u{:04x}", chr as u32),
+            chr => output.push(chr),
+        }
+    }
+    output.push('"');
+    output
+}
+
+fn array<T: IntoIterator<Item = String>>(items: T) -> String {
+    format!("[{}]", items.into_iter().collect::<Vec<_>>().join(","))
+}
+
+fn object<'a, T: IntoIterator<Item = (&'a str, String)>>(members: T) -> String {
+    let members: Vec<String> = members
+        .into_iter()
+        .map(|(key, value)| format!("{}:{}", string(key), value))
+        .collect();
+    format!("{{{}}}", members.join(","))
+}
+
+fn hai_vec(hai_vec: &[calculator::Hai]) -> String {
+    array(hai_vec.iter().map(|hai| string(&hai.to_string())))
+}
+
+fn machihai(machihai: &std::collections::BTreeMap<calculator::Hai, u8>) -> String {
+    let members: Vec<(String, String)> = machihai
+        .iter()
+        .map(|(hai, number)| (hai.to_string(), number.to_string()))
+        .collect();
+    object(members.iter().map(|(hai, number)| (hai.as_str(), number.clone())))
+}
+
+/// Fuuro like `{"kind":"Pon","hai":["5p","5p","5p"],"called":"5p","from":"Toimen"}`,
+/// `called` and `from` are `null` if unknown.
+fn fuuro(fuuro: &calculator::Fuuro) -> String {
+    object([
+        ("kind", string(&format!("{:?}", fuuro.kind))),
+        ("hai", hai_vec(&fuuro.hai_vec())),
+        (
+            "called",
+            fuuro
+                .called
+                .map_or("null".to_string(), |hai| string(&hai.to_string())),
+        ),
+        (
+            "from",
+            fuuro
+                .from
+                .map_or("null".to_string(), |from| string(&format!("{:?}", from))),
+        ),
+    ])
+}
+
+/// Members shared by every output, like `"tehai":"1m2m3m"`.
+fn tehai_members<'a>(
+    tehai: &calculator::Tehai,
+    taku: &calculator::Taku,
+    shanten: i32,
+) -> Vec<(&'a str, String)> {
+    vec![
+        ("tehai", string(&tehai.to_string())),
+        ("juntehai", hai_vec(&tehai.juntehai)),
+        (
+            "fuuro",
+            array(tehai.fuuro.iter().map(fuuro)),
+        ),
+        ("nukidora", tehai.nukidora.to_string()),
+        ("player_number", tehai.player_number.to_string()),
+        ("dora", tehai.dora(&taku.dora_hyouji).to_string()),
+        ("uradora", tehai.dora(&taku.uradora_hyouji).to_string()),
+        ("aka", tehai.aka().to_string()),
+        ("shanten", shanten.to_string()),
+    ]
+}
+
+/// Output of 3*k+2 tehai, with all conditions of sutehai.
+pub fn machi(
+    tehai: &calculator::Tehai,
+    taku: &calculator::Taku,
+    shanten: i32,
+    conditions: &[calculator::MachiCondition],
+) -> String {
+    let mut members = tehai_members(tehai, taku, shanten);
+    members.push((
+        "conditions",
+        array(conditions.iter().map(|condition| {
+            object([
+                ("sutehai", string(&condition.sutehai.to_string())),
+                ("machihai", machihai(&condition.machihai)),
+                ("nokori", condition.nokori().to_string()),
+                ("furiten", condition.furiten.to_string()),
+            ])
+        })),
+    ));
+    object(members)
+}
+
+/// Output of complete tehai, with points of the given agari hai, or of the best one if
+/// `guessed`. Tensuu is `null` if no yaku.
+pub fn agari(
+    tehai: &calculator::Tehai,
+    taku: &calculator::Taku,
+    best: &Option<(calculator::Hai, calculator::Tensuu)>,
+    tsumo: bool,
+    guessed: bool,
+) -> String {
+    let mut members = tehai_members(tehai, taku, -1);
+    members.push(("tsumo", tsumo.to_string()));
+    members.push(("agari_hai_guessed", guessed.to_string()));
+    members.push((
+        "tensuu",
+        match best {
+            Some((agari_hai, tensuu)) => self::tensuu(*agari_hai, tensuu),
+            None => "null".to_string(),
+        },
+    ));
+    object(members)
+}
+
+/// Output of 3*k+1 tehai.
+pub fn ukeire(
+    tehai: &calculator::Tehai,
+    taku: &calculator::Taku,
+    ukeire: &calculator::Ukeire,
+) -> String {
+    let mut members = tehai_members(tehai, taku, ukeire.shanten);
+    members.extend(ukeire_members(ukeire));
+    object(members)
+}
+
+/// Output of 3*k+1 tehai, with kairyouhai.
+pub fn kairyou(
+    tehai: &calculator::Tehai,
+    taku: &calculator::Taku,
+    kairyou: &calculator::Kairyou,
+) -> String {
+    let mut members = tehai_members(tehai, taku, kairyou.ukeire.shanten);
+    members.extend(ukeire_members(&kairyou.ukeire));
+    members.extend(kairyou_members(kairyou));
+    object(members)
+}
+
+/// Output of 3*k+2 tehai, with ukeire and kairyouhai of each sutehai.
+pub fn kairyou_conditions(
+    tehai: &calculator::Tehai,
+    taku: &calculator::Taku,
+    shanten: i32,
+    conditions: &[(calculator::Hai, calculator::Kairyou)],
+) -> String {
+    let mut members = tehai_members(tehai, taku, shanten);
+    members.push((
+        "conditions",
+        array(conditions.iter().map(|(sutehai, kairyou)| {
+            let mut condition_members = vec![("sutehai", string(&sutehai.to_string()))];
+            condition_members.push(("shanten", kairyou.ukeire.shanten.to_string()));
+            condition_members.extend(ukeire_members(&kairyou.ukeire));
+            condition_members.extend(kairyou_members(kairyou));
+            object(condition_members)
+        })),
+    ));
+    object(members)
+}
+
+fn kairyou_members<'a>(kairyou: &calculator::Kairyou) -> Vec<(&'a str, String)> {
+    vec![
+        (
+            "kairyouhai",
+            array(kairyou.kairyouhai.iter().map(|kairyouhai| {
+                object([
+                    ("hai", string(&kairyouhai.hai.to_string())),
+                    ("nokori", kairyouhai.nokori.to_string()),
+                    ("sutehai", string(&kairyouhai.sutehai.to_string())),
+                    ("ukeire", kairyouhai.ukeire.to_string()),
+                ])
+            })),
+        ),
+        ("average", format!("{:.4}", kairyou.average)),
+    ]
+}
+
+fn ukeire_members<'a>(ukeire: &calculator::Ukeire) -> Vec<(&'a str, String)> {
+    let machi: Vec<(String, String)> = ukeire
+        .machi
+        .iter()
+        .map(|(hai, machi_vec)| {
+            (
+                hai.to_string(),
+                array(machi_vec.iter().map(|machi| string(&format!("{:?}", machi)))),
+            )
+        })
+        .collect();
+    vec![
+        ("machihai", machihai(&ukeire.machihai)),
+        ("nokori", ukeire.nokori().to_string()),
+        ("furiten", ukeire.furiten.to_string()),
+        (
+            "machi",
+            object(machi.iter().map(|(hai, machi)| (hai.as_str(), machi.clone()))),
+        ),
+    ]
+}
+
+fn tensuu(agari_hai: calculator::Hai, tensuu: &calculator::Tensuu) -> String {
+    object([
+        ("agari_hai", string(&agari_hai.to_string())),
+        ("machi", string(&format!("{:?}", tensuu.decomposer.machi))),
+        (
+            "yaku",
+            array(tensuu.yaku_vec.iter().map(|yaku| {
+                object([
+                    ("name", string(&yaku.to_string())),
+                    ("han", yaku.han(tensuu.menzen).to_string()),
+                    ("yakuman", yaku.yakuman().to_string()),
+                ])
+            })),
+        ),
+        ("dora", tensuu.dora.to_string()),
+        ("aka", tensuu.aka.to_string()),
+        ("nukidora", tensuu.nukidora.to_string()),
+        ("han", tensuu.han.to_string()),
+        ("fu", tensuu.fu.to_string()),
+        (
+            "limit",
+            match tensuu.limit {
+                Some(limit) => string(&limit.to_string()),
+                None => "null".to_string(),
+            },
+        ),
+        (
+            "shiharai",
+            match tensuu.shiharai {
+                calculator::Shiharai::Ron(points) => object([("ron", points.to_string())]),
+                calculator::Shiharai::TsumoOya(points) => {
+                    object([("tsumo_oya", points.to_string())])
+                }
+                calculator::Shiharai::TsumoKo { oya, ko } => object([
+                    ("tsumo_oya", oya.to_string()),
+                    ("tsumo_ko", ko.to_string()),
+                ]),
+            },
+        ),
+        ("kyoutaku", tensuu.kyoutaku.to_string()),
+        ("total", tensuu.total().to_string()),
+    ])
+}
+
+/// Output of a line in batch, with the result from `machi` or the error from `error`.
+pub fn batch_line(
+    line_number: usize,
+    input: &str,
+    result: &Result<String, calculator::Error>,
+) -> String {
+    object([
+        ("line", line_number.to_string()),
+        ("input", string(input)),
+        match result {
+            Ok(result) => ("result", result.clone()),
+            Err(error) => ("error", self::error(error)),
+        },
+    ])
+}
+
+/// Output at the end of batch, like `{"summary":{"total":3,"analyzed":2,"failed":1}}`.
+pub fn batch_summary(summary: &super::BatchSummary) -> String {
+    object([(
+        "summary",
+        object([
+            ("total", summary.total.to_string()),
+            ("analyzed", summary.analyzed.to_string()),
+            ("failed", summary.failed.to_string()),
+        ]),
+    )])
+}
+
+/// Output of any error, like
+/// `{"error":"Unknown character 'x' at index 3.","kind":"UnknownCharacter","index":3}`.
+///
+/// `index` and `hai` are included only if the error has them.
+pub fn error(error: &calculator::Error) -> String {
+    let kind = match error {
+        calculator::Error::UnusedTypeCharacter { .. } => "UnusedTypeCharacter",
+        calculator::Error::MissingTypeCharacter { .. } => "MissingTypeCharacter",
+        calculator::Error::UnusedAkaCharacter { .. } => "UnusedAkaCharacter",
+        calculator::Error::UnknownCharacter { .. } => "UnknownCharacter",
+        calculator::Error::InvalidHai { .. } => "InvalidHai",
+        calculator::Error::InvalidMentsu { .. } => "InvalidMentsu",
+        calculator::Error::NestedBracket { .. } => "NestedBracket",
+        calculator::Error::UnmatchedBracket { .. } => "UnmatchedBracket",
+        calculator::Error::UnclosedBracket { .. } => "UnclosedBracket",
+        calculator::Error::UnexpectedBracket { .. } => "UnexpectedBracket",
+        calculator::Error::TooManyHai { .. } => "TooManyHai",
+        calculator::Error::InvalidTakuHai(_) => "InvalidTakuHai",
+        calculator::Error::WrongHaiNumber { .. } => "WrongHaiNumber",
+        calculator::Error::AgariHaiNotFound(_) => "AgariHaiNotFound",
+        calculator::Error::HaiNotFound(_) => "HaiNotFound",
+        calculator::Error::InvalidKaze(_) => "InvalidKaze",
+        calculator::Error::NotAgari => "NotAgari",
+        calculator::Error::Logic(_) => "Logic",
+    };
+
+    let mut members = vec![("error", string(&error.to_string())), ("kind", string(kind))];
+    if let Some(index) = error.index() {
+        members.push(("index", index.to_string()));
+    }
+    if let Some(hai) = error.hai() {
+        members.push(("hai", string(&hai.to_string())));
+    }
+    object(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{Controller, OutputFormat};
+
+    fn execute(controller: &mut Controller, command: &str) -> String {
+        controller.execute(command.to_string())
+    }
+
+    #[test]
+    fn escape_string() {
+        assert_eq!(string("1m"), r#""1m""#);
+        assert_eq!(string("\"\\\n\r\t\u{1}東"), r#""\"\\\n\r\t\u0001東""#);
+    }
+
+    #[test]
+    fn ukeire_and_machi() {
+        let mut controller = Controller::new(OutputFormat::Json, 4);
+        assert_eq!(
+            execute(&mut controller, "1m"),
+            concat!(
+                r#"{"tehai":"1m","juntehai":["1m"],"fuuro":[],"nukidora":0,"player_number":4,"#,
+                r#""dora":0,"uradora":0,"aka":0,"shanten":0,"machihai":{"1m":3},"nokori":3,"#,
+                r#""furiten":false,"machi":{"1m":["Tanki"]}}"#
+            )
+        );
+        assert_eq!(
+            execute(&mut controller, "12m[111z]"),
+            concat!(
+                r#"{"tehai":"1m2m[1z1z1z]","juntehai":["1m","2m"],"#,
+                r#""fuuro":[{"kind":"Pon","hai":["1z","1z","1z"],"called":null,"from":null}],"#,
+                r#""nukidora":0,"player_number":4,"dora":0,"uradora":0,"aka":0,"shanten":0,"#,
+                r#""conditions":[{"sutehai":"1m","machihai":{"2m":3},"nokori":3,"furiten":false},"#,
+                r#"{"sutehai":"2m","machihai":{"1m":3},"nokori":3,"furiten":false}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn agari() {
+        let mut controller = Controller::new(OutputFormat::Json, 4);
+        controller.set_agari_hai(Some(calculator::Hai::Manzu(1)));
+        controller.set_tsumo(true);
+        assert_eq!(
+            execute(&mut controller, "11m"),
+            concat!(
+                r#"{"tehai":"1m1m","juntehai":["1m","1m"],"fuuro":[],"nukidora":0,"#,
+                r#""player_number":4,"dora":0,"uradora":0,"aka":0,"shanten":-1,"#,
+                r#""tsumo":true,"agari_hai_guessed":false,"#,
+                r#""tensuu":{"agari_hai":"1m","machi":"Tanki","#,
+                r#""yaku":[{"name":"清老頭","han":13,"yakuman":1}],"#,
+                r#""dora":0,"aka":0,"nukidora":0,"han":13,"fu":30,"limit":"役満","#,
+                r#""shiharai":{"tsumo_oya":16000,"tsumo_ko":8000},"kyoutaku":0,"total":32000}}"#
+            )
+        );
+
+        // No yaku with any agari hai.
+        let mut controller = Controller::new(OutputFormat::Json, 4);
+        let output = execute(&mut controller, "123m456p789s11z");
+        assert!(
+            output.ends_with(r#""tsumo":false,"agari_hai_guessed":true,"tensuu":null}"#),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn error_object() {
+        let mut controller = Controller::new(OutputFormat::Json, 4);
+        assert_eq!(
+            execute(&mut controller, "1x"),
+            r#"{"error":"Unknown character 'x' at index 1.","kind":"UnknownCharacter","index":1}"#
+        );
+        assert_eq!(
+            execute(&mut controller, "11111m"),
+            concat!(
+                r#"{"error":"Fifth 1m found at index 4.","kind":"TooManyHai","index":4,"#,
+                r#""hai":"1m"}"#
+            )
+        );
+        assert_eq!(
+            error(&calculator::Error::NotAgari),
+            format!(
+                r#"{{"error":{},"kind":"NotAgari"}}"#,
+                string(&calculator::Error::NotAgari.to_string())
+            )
+        );
+    }
+}
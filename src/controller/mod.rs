@@ -1,2 +1,8 @@
+mod batch;
+#[allow(clippy::module_inception)]
 mod controller;
-pub use controller::{Controller, OutputFormat};
+mod json;
+mod repl;
+pub use batch::BatchSummary;
+pub use controller::{ArgsError, Controller, OutputFormat};
+pub use repl::Repl;
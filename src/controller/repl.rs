@@ -0,0 +1,368 @@
+use super::Controller;
+use crate::calculator;
+use std::io::{BufRead, Write};
+
+const HELP: &str = "\
+<tehai>              start with a new tehai, like 1122345m4689s357p
++<hai>               draw hai, like +5m
+-<hai>               discard hai into kawa, like -3p
+call <fuuro>         call in the same way as tehai input, like call [<312m] or call (1111z)
+call <mentsu> <hai>  call mentsu with hai from others, like call 123m 3m
+call <kantsu>        kakan on a called koutsu, like call 5555p
+dora <hai>           set dora indicators, like dora 1z3p
+ura <hai>            set uradora indicators
+tacha <hai>          set hai visible from other players
+wind <hai>           set wind of the player, 1z to 4z
+undo                 undo the last change
+history              show all changes
+help                 show this message
+quit                 exit";
+
+/// Everything the player entered, restored by undo.
+#[derive(Clone, Debug)]
+struct State {
+    tehai: calculator::Tehai,
+    dora_hyouji: Vec<calculator::Hai>,
+    uradora_hyouji: Vec<calculator::Hai>,
+    kawa: Vec<calculator::Hai>,
+    tacha_kawa: Vec<calculator::Hai>,
+    jikaze: calculator::Hai,
+}
+
+/// Read-eval-print loop for walking through a hand step by step.
+///
+/// Each line is a command changing the tehai or the taku, and the tehai is analyzed again
+/// by the controller after each change. See `help` for all commands.
+pub struct Repl {
+    controller: Controller,
+    state: State,
+    history: Vec<(String, State)>,
+}
+
+impl Repl {
+    pub fn new(controller: Controller) -> Self {
+        let player_number = controller.player_number();
+        let jikaze = controller.jikaze();
+        Self {
+            controller,
+            state: State {
+                tehai: calculator::Tehai::with_player_number(String::new(), player_number)
+                    .expect("Empty tehai is always valid."),
+                dora_hyouji: vec![],
+                uradora_hyouji: vec![],
+                kawa: vec![],
+                tacha_kawa: vec![],
+                jikaze,
+            },
+            history: vec![],
+        }
+    }
+
+    /// Read commands from `input` till the end or `quit`, writing results to `output`.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> std::io::Result<()> {
+        write!(output, "> ")?;
+        output.flush()?;
+        for line in input.lines() {
+            match self.execute(&line?) {
+                Some(result) => {
+                    if !result.is_empty() {
+                        writeln!(output, "{}", result)?;
+                    }
+                }
+                None => return Ok(()),
+            }
+            write!(output, "> ")?;
+            output.flush()?;
+        }
+        writeln!(output)
+    }
+
+    /// Execute a line, returning what to print, or `None` for `quit`.
+    pub fn execute(&mut self, line: &str) -> Option<String> {
+        let line = line.trim();
+        let (command, argument) = line
+            .split_once(char::is_whitespace)
+            .map_or((line, ""), |(command, argument)| (command, argument.trim()));
+
+        match command {
+            "" => Some(String::new()),
+            "quit" | "exit" => None,
+            "help" => Some(HELP.to_string()),
+            "history" => Some(
+                self.history
+                    .iter()
+                    .enumerate()
+                    .map(|(index, (line, _))| format!("{} {}", index + 1, line))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            "undo" => Some(match self.history.pop() {
+                Some((_, state)) => {
+                    self.state = state;
+                    self.analyze()
+                }
+                None => "Nothing to undo.".to_string(),
+            }),
+            _ => {
+                let mut state = self.state.clone();
+                Some(match self.apply(&mut state, command, argument) {
+                    Ok(()) => {
+                        self.history
+                            .push((line.to_string(), std::mem::replace(&mut self.state, state)));
+                        self.analyze()
+                    }
+                    Err(error) => self.controller.error_string(&error),
+                })
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        state: &mut State,
+        command: &str,
+        argument: &str,
+    ) -> Result<(), calculator::Error> {
+        let player_number = self.controller.player_number();
+        let parse_hai_vec = |string: &str| calculator::Taku::parse_hai_vec(string, player_number);
+
+        match command {
+            "dora" => state.dora_hyouji = parse_hai_vec(argument)?,
+            "ura" => state.uradora_hyouji = parse_hai_vec(argument)?,
+            "tacha" => state.tacha_kawa = parse_hai_vec(argument)?,
+            "wind" => {
+                let jikaze = match parse_hai_vec(argument)?[..] {
+                    [] => calculator::Hai::Jihai(2),
+                    [hai] => hai,
+                    [_, hai, ..] => return Err(calculator::Error::InvalidKaze(hai)),
+                };
+                if !matches!(jikaze, calculator::Hai::Jihai(1..=4)) {
+                    return Err(calculator::Error::InvalidKaze(jikaze));
+                }
+                state.jikaze = jikaze;
+            }
+            "call" => {
+                let mut argument = argument.split_whitespace();
+                let mut fuuro_string = argument.next().unwrap_or_default().to_string();
+                if !fuuro_string.starts_with(['[', '(']) {
+                    fuuro_string = format!("[{}]", fuuro_string);
+                }
+                let called = parse_hai_vec(argument.next().unwrap_or_default())?;
+                let fuuro = calculator::Tehai::with_player_number(fuuro_string, player_number)?
+                    .fuuro
+                    .pop()
+                    .ok_or(calculator::Error::Logic("Fuuro not parsed."))?;
+                call(&mut state.tehai, fuuro, called.first().copied())?;
+            }
+            _ if command.starts_with('+') => {
+                let hai_vec = parse_hai_vec(&command[1..])?;
+                state.tehai.juntehai.extend(hai_vec);
+                state.tehai.juntehai.sort();
+            }
+            _ if command.starts_with('-') => {
+                for hai in parse_hai_vec(&command[1..])? {
+                    let sutehai = remove(&mut state.tehai.juntehai, hai)?;
+                    state.kawa.push(sutehai);
+                }
+            }
+            _ => {
+                state.tehai = calculator::Tehai::with_player_number(
+                    format!("{}{}", command, argument),
+                    player_number,
+                )?;
+                state.kawa.clear();
+            }
+        }
+
+        // Parse again to check the number of each hai.
+        state.tehai =
+            calculator::Tehai::with_player_number(state.tehai.to_string(), player_number)?;
+        Ok(())
+    }
+
+    fn analyze(&mut self) -> String {
+        let hai_string = |hai_vec: &[calculator::Hai]| {
+            hai_vec
+                .iter()
+                .map(|hai| hai.to_string())
+                .collect::<String>()
+        };
+        self.controller
+            .set_dora_hyouji(hai_string(&self.state.dora_hyouji));
+        self.controller
+            .set_uradora_hyouji(hai_string(&self.state.uradora_hyouji));
+        self.controller.set_kawa(hai_string(&self.state.kawa));
+        self.controller
+            .set_tacha_kawa(hai_string(&self.state.tacha_kawa));
+        self.controller.set_jikaze(self.state.jikaze);
+        self.controller.execute(self.state.tehai.to_string())
+    }
+}
+
+/// Remove a hai, returning the one removed. Red five and normal five are taken for each
+/// other if the same one is not found.
+fn remove(
+    juntehai: &mut Vec<calculator::Hai>,
+    hai: calculator::Hai,
+) -> Result<calculator::Hai, calculator::Error> {
+    let position = juntehai
+        .iter()
+        .position(|cur| *cur == hai)
+        .or_else(|| {
+            juntehai
+                .iter()
+                .position(|cur| cur.normalize() == hai.normalize())
+        })
+        .ok_or(calculator::Error::HaiNotFound(hai))?;
+    Ok(juntehai.remove(position))
+}
+
+/// Move hai of fuuro from juntehai, except the one called from others.
+///
+/// Kakan, or kantsu without called hai while the koutsu is already called, upgrades the
+/// pon. If the called hai is unknown, the only one not in juntehai is taken as it.
+fn call(
+    tehai: &mut calculator::Tehai,
+    mut fuuro: calculator::Fuuro,
+    called: Option<calculator::Hai>,
+) -> Result<(), calculator::Error> {
+    fuuro.called = fuuro.called.or(called);
+    let mut hai_vec = fuuro.hai_vec();
+
+    let pon_position = tehai.fuuro.iter().position(|pon| {
+        pon.kind == calculator::FuuroKind::Pon
+            && pon.mentsu.normalize() == calculator::Mentsu::Koutsu(fuuro.mentsu.hai_vec()[0])
+    });
+    let kakan = fuuro.kind == calculator::FuuroKind::Kakan
+        || (fuuro.kind == calculator::FuuroKind::Daiminkan
+            && fuuro.called.is_none()
+            && pon_position.is_some());
+
+    if kakan {
+        let pon = tehai
+            .fuuro
+            .remove(pon_position.ok_or(calculator::Error::HaiNotFound(fuuro.mentsu.hai_vec()[0]))?);
+        for hai in pon.hai_vec() {
+            remove(&mut hai_vec, hai)?;
+        }
+        fuuro.kind = calculator::FuuroKind::Kakan;
+        fuuro.called = fuuro.called.or(pon.called);
+        fuuro.from = fuuro.from.or(pon.from);
+    } else if let (true, Some(called)) = (fuuro.is_open(), fuuro.called) {
+        remove(&mut hai_vec, called)?;
+    } else if fuuro.is_open() {
+        let missing: Vec<calculator::Hai> = hai_vec
+            .iter()
+            .filter(|hai| remove(&mut tehai.juntehai, **hai).is_err())
+            .copied()
+            .collect();
+        match missing[..] {
+            [] => tehai.juntehai.extend(hai_vec.last()),
+            [_] => (),
+            [_, hai, ..] => return Err(calculator::Error::HaiNotFound(hai)),
+        }
+        hai_vec.clear();
+    }
+
+    for hai in hai_vec {
+        remove(&mut tehai.juntehai, hai)?;
+    }
+    tehai.juntehai.sort();
+    tehai.fuuro.push(fuuro);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::OutputFormat;
+    use calculator::{Fuuro, FuuroKind, Hai, Tacha, Tehai};
+
+    fn start(lines: &[&str]) -> Repl {
+        let mut repl = Repl::new(Controller::new(OutputFormat::Standard, 4));
+        for line in lines {
+            repl.execute(line).unwrap();
+        }
+        repl
+    }
+
+    fn tehai(string: &str) -> Tehai {
+        Tehai::new(string.to_string()).unwrap()
+    }
+
+    #[test]
+    fn draw_and_discard() {
+        let repl = start(&["123406m11p789s11z", "+5m", "-0m"]);
+        assert_eq!(repl.state.tehai, tehai("123456m11p789s11z"));
+        assert_eq!(repl.state.kawa, [Hai::Manzu(0)]);
+
+        // Red five and normal five are taken for each other.
+        let repl = start(&["123456m11p789s11z", "+2z", "-0m"]);
+        assert_eq!(repl.state.tehai, tehai("12346m11p789s112z"));
+        assert_eq!(repl.state.kawa, [Hai::Manzu(5)]);
+        let repl = start(&["123406m11p789s11z", "+2z", "-5m"]);
+        assert_eq!(repl.state.tehai, tehai("12346m11p789s112z"));
+        assert_eq!(repl.state.kawa, [Hai::Manzu(0)]);
+
+        let mut repl = start(&["123456m11p789s11z"]);
+        let error = repl.execute("-3p").unwrap();
+        assert!(error.contains("3p"), "{}", error);
+        assert_eq!(repl.state.tehai, tehai("123456m11p789s11z"));
+        assert_eq!(repl.history.len(), 1);
+    }
+
+    #[test]
+    fn call_and_kakan() {
+        let mut repl = start(&["123456m11p789s11z", "call [^111z]"]);
+        assert_eq!(repl.state.tehai, tehai("123456m11p789s[1^11z]"));
+        repl.execute("call [<789s]").unwrap();
+        assert_eq!(repl.state.tehai, tehai("123456m11p7s[1^11z][<789s]"));
+
+        // Called hai given after mentsu.
+        let repl = start(&["123456m11p789s11z", "call 789s 9s"]);
+        assert_eq!(repl.state.tehai, tehai("123456m11p9s11z[789s]"));
+
+        // Open call infers the called hai as the one not in juntehai.
+        let repl = start(&["123456m11p79s11z", "call 789s"]);
+        assert_eq!(repl.state.tehai, tehai("123456m11p11z[789s]"));
+        let mut repl = start(&["123456m11p11z"]);
+        let error = repl.execute("call 789s").unwrap();
+        assert!(error.contains("8s"), "{}", error);
+
+        // Kakan upgrades the pon, keeping the called hai.
+        let mut repl = start(&["123456m11p789s1z[1^11z]", "+1z"]);
+        repl.execute("call 1111z").unwrap();
+        assert_eq!(repl.state.tehai.juntehai, tehai("123456m11p789s").juntehai);
+        assert_eq!(repl.state.tehai.fuuro.len(), 1);
+        let kakan: Fuuro = repl.state.tehai.fuuro[0];
+        assert_eq!(kakan.kind, FuuroKind::Kakan);
+        assert_eq!(
+            (kakan.called, kakan.from),
+            (Some(Hai::Jihai(1)), Some(Tacha::Toimen))
+        );
+    }
+
+    #[test]
+    fn undo_and_history() {
+        let mut repl = start(&["123456m11p789s11z", "+5z", "-1m", "dora 1z"]);
+        assert_eq!(
+            repl.execute("history").unwrap(),
+            "1 123456m11p789s11z\n2 +5z\n3 -1m\n4 dora 1z"
+        );
+        assert_eq!(repl.state.dora_hyouji, [Hai::Jihai(1)]);
+
+        repl.execute("undo").unwrap();
+        assert!(repl.state.dora_hyouji.is_empty());
+        repl.execute("undo").unwrap();
+        assert_eq!(repl.state.tehai, tehai("123456m11p789s115z"));
+        assert!(repl.state.kawa.is_empty());
+        let history = repl.execute("history").unwrap();
+        assert_eq!(history, "1 123456m11p789s11z\n2 +5z");
+
+        repl.execute("undo").unwrap();
+        repl.execute("undo").unwrap();
+        assert_eq!(repl.state.tehai, tehai(""));
+        assert_eq!(repl.execute("undo").unwrap(), "Nothing to undo.");
+        assert_eq!(repl.execute("quit"), None);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::calculator::{self, Hai};
+
+/// Error of an action in a hanchan. Nothing is changed by the action.
+///
+/// All seats are 0\~3, 0 for the first oya.
+///
+/// # Variant
+/// * WrongPhase: the action is not expected now, like answering when a player should discard.
+/// * HaiNotFound: hai to be discarded or added by kakan is not in juntehai of the player.
+/// * Tsumogiri: player in riichi must discard the hai just drawn.
+/// * InvalidRiichi: riichi without menzen, tenpai, 1000 points or 4 hai left to draw.
+/// * Kuikae: hai not allowed to be discarded right after calling, same as the called one or
+///   the other end of the juntsu.
+/// * InvalidNaki: the call of the player is not allowed, see `naki_candidates`.
+/// * InvalidKan: kan of hai is not allowed now.
+/// * NotAgari: tehai of the player is not complete.
+/// * NoYaku: tehai of the player is complete but without yaku.
+/// * Furiten: ron by the player while furiten.
+/// * Calculator: error of analyzing tehai, which should not happen with hai from yama.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    WrongPhase,
+    HaiNotFound { seat: usize, hai: Hai },
+    Tsumogiri(Hai),
+    InvalidRiichi,
+    Kuikae(Hai),
+    InvalidNaki { seat: usize },
+    InvalidKan(Hai),
+    NotAgari { seat: usize },
+    NoYaku { seat: usize },
+    Furiten { seat: usize },
+    Calculator(calculator::Error),
+}
+
+impl From<calculator::Error> for Error {
+    fn from(error: calculator::Error) -> Self {
+        Error::Calculator(error)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::WrongPhase => write!(f, "The action is not expected now."),
+            Error::HaiNotFound { seat, hai } => {
+                write!(f, "{} is not in juntehai of seat {}.", hai, seat)
+            }
+            Error::Tsumogiri(hai) => write!(f, "Must discard {} just drawn after riichi.", hai),
+            Error::InvalidRiichi => write!(f, "Riichi is not allowed."),
+            Error::Kuikae(hai) => write!(f, "{} cannot be discarded right after calling.", hai),
+            Error::InvalidNaki { seat } => write!(f, "The call of seat {} is not allowed.", seat),
+            Error::InvalidKan(hai) => write!(f, "Kan of {} is not allowed.", hai),
+            Error::NotAgari { seat } => write!(f, "Tehai of seat {} is not complete.", seat),
+            Error::NoYaku { seat } => write!(f, "Tehai of seat {} has no yaku.", seat),
+            Error::Furiten { seat } => write!(f, "Seat {} is furiten.", seat),
+            Error::Calculator(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,141 @@
+use crate::calculator::{Fuuro, Hai, Tensuu};
+
+/// Call on sutehai of others, or on kakan for chankan.
+///
+/// # Japanese
+/// * Naki: 鳴き
+/// * Ron: 栄和
+/// * Chii: 吃
+/// * Pon: 碰
+/// * Daiminkan: 大明槓
+///
+/// # Variant
+/// * Ron: win by the hai.
+/// * Chii: make juntsu with the two hai in juntehai, only on sutehai of kamicha.
+/// * Pon: make koutsu with the two hai in juntehai, which decide if red five is used.
+/// * Daiminkan: make kantsu with all three same hai in juntehai.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Naki {
+    Ron,
+    Chii(Hai, Hai),
+    Pon(Hai, Hai),
+    Daiminkan,
+}
+
+/// Action of players, as expected by `Phase`.
+///
+/// # Japanese
+/// * dahai: 打牌
+/// * tsumo: 自摸和
+/// * ankan: 暗槓
+/// * kakan: 加槓
+///
+/// # Variant
+/// * Dahai: discard hai, declaring riichi if `riichi`. Expected by `Phase::Dahai`.
+/// * Tsumo: win by the hai just drawn. Expected by `Phase::Dahai`.
+/// * Ankan: ankan of the hai after drawing. Expected by `Phase::Dahai`.
+/// * Kakan: add the hai to a pon after drawing. Expected by `Phase::Dahai`.
+/// * Answer: calls of every player by seat, `None` to pass. Expected by `Phase::Naki` and
+///   `Phase::Chankan`, where the answer of the player making the sutehai or kakan is ignored.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Dahai { hai: Hai, riichi: bool },
+    Tsumo,
+    Ankan(Hai),
+    Kakan(Hai),
+    Answer([Option<Naki>; 4]),
+}
+
+/// What a hanchan is waiting for.
+///
+/// # Japanese
+/// * chankan: 槍槓
+/// * shuukyoku: 終局
+///
+/// # Variant
+/// * Dahai: the player of `seat` to discard, tsumo or kan, after drawing or calling.
+/// * Naki: all other players to answer `hai` discarded by `seat`.
+/// * Chankan: all other players to answer kakan of `hai` by `seat`, only ron allowed.
+/// * Shuukyoku: the hanchan is over.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Phase {
+    Dahai { seat: usize },
+    Naki { seat: usize, hai: Hai },
+    Chankan { seat: usize, hai: Hai },
+    Shuukyoku,
+}
+
+/// Everything happening in a hanchan, in order.
+///
+/// # Japanese
+/// * haipai: 配牌
+/// * kandora: 槓ドラ
+/// * agari: 和了
+/// * ryuukyoku: 流局
+/// * tenpai: 聴牌
+/// * nagashi mangan: 流し満貫
+///
+/// # Variant
+/// * KyokuStart: a kyoku started, with points of all players at the start.
+/// * Haipai: 13 hai dealt to the player.
+/// * Tsumo: the player drew hai, from wanpai if `rinshan`.
+/// * Dahai: the player discarded hai, declaring riichi if `riichi`.
+/// * Riichi: riichi accepted after the sutehai passed, with 1000 points put on the table.
+/// * Fuuro: the player made a fuuro, by calling or by kan.
+/// * Kandora: a kandora indicator revealed.
+/// * Agari: the player of `seat` won by hai from `from`, the same seat for tsumo, with
+///   points change of every player.
+/// * Ryuukyoku: no hai left to draw, with tenpai, nagashi mangan and points change of every
+///   player.
+/// * Shuukyoku: the hanchan is over, with final points.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    KyokuStart {
+        bakaze: Hai,
+        kyoku: u8,
+        honba: u8,
+        kyoutaku: u8,
+        dora_hyouji: Hai,
+        points: [i32; 4],
+    },
+    Haipai {
+        seat: usize,
+        juntehai: Vec<Hai>,
+    },
+    Tsumo {
+        seat: usize,
+        hai: Hai,
+        rinshan: bool,
+    },
+    Dahai {
+        seat: usize,
+        hai: Hai,
+        tsumogiri: bool,
+        riichi: bool,
+    },
+    Riichi {
+        seat: usize,
+    },
+    Fuuro {
+        seat: usize,
+        fuuro: Fuuro,
+    },
+    Kandora {
+        hai: Hai,
+    },
+    Agari {
+        seat: usize,
+        from: usize,
+        hai: Hai,
+        tensuu: Box<Tensuu>,
+        delta: [i32; 4],
+    },
+    Ryuukyoku {
+        tenpai: [bool; 4],
+        nagashi_mangan: [bool; 4],
+        delta: [i32; 4],
+    },
+    Shuukyoku {
+        points: [i32; 4],
+    },
+}
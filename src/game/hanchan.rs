@@ -0,0 +1,1335 @@
+use super::{naki_candidates, resolve_naki};
+use super::{Action, Error, Event, Naki, NakiRule, Phase, Player, Ryuukyoku, Sutehai};
+use crate::calculator::{
+    AgariCondition, Fuuro, FuuroKind, Hai, Mentsu, Shiharai, Tacha, Tensuu, Yama,
+};
+
+/// Number of players of a hanchan.
+const PLAYER_NUMBER: usize = 4;
+
+/// Most number of kan in a kyoku.
+const MAX_KAN_NUMBER: usize = 4;
+
+/// Rules of a hanchan.
+///
+/// # Japanese
+/// * agari_yame: 和了止め
+/// * tobi: 飛び
+///
+/// # Member
+/// * start_points: points of each player at the start.
+/// * agari_yame: if the hanchan ends when oya of the last kyoku renchan as the top.
+/// * tobi: if the hanchan ends when anyone has points below 0.
+/// * naki: rules of resolving calls on the same hai.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    pub start_points: i32,
+    pub agari_yame: bool,
+    pub tobi: bool,
+    pub naki: NakiRule,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self {
+            start_points: 25000,
+            agari_yame: true,
+            tobi: true,
+            naki: NakiRule::default(),
+        }
+    }
+}
+
+/// A hanchan of 4 players, from east 1 to south 4.
+///
+/// # Japanese
+/// * Hanchan: 半荘
+/// * bakaze: 場風
+/// * kyoku: 局
+/// * honba: 本場
+/// * kyoutaku: 供託
+/// * oya: 親
+/// * renchan: 連荘
+///
+/// Driven by `step` with actions expected by `phase`, emitting events. Seats are 0\~3 in
+/// the order of turns, and seat 0 is the first oya. Each kyoku deals a new `Yama` from the
+/// seed, so the same seed and actions always make the same hanchan.
+///
+/// Oya renchan by winning, or by tenpai or nagashi mangan at ryuukyoku, and honba
+/// increases with renchan and ryuukyoku. Ron of more than one player is resolved by
+/// `rule.naki`, and honba and kyoutaku go to the first one in turn order from the
+/// discarder. Kandora is revealed right after every kan, and no more kan after the fourth
+/// one. Kyoutaku left at the end goes to the top. There are no west round, no pao and no
+/// abortive draw.
+#[derive(Clone, Debug)]
+pub struct Hanchan {
+    rule: Rule,
+    seed: u64,
+    kyoku_number: u64,
+    points: [i32; PLAYER_NUMBER],
+    bakaze: Hai,
+    kyoku: u8,
+    honba: u8,
+    kyoutaku: u8,
+    yama: Yama,
+    players: [Player; PLAYER_NUMBER],
+    phase: Phase,
+    // The hai just drawn, `None` after calling.
+    tsumo_hai: Option<Hai>,
+    rinshan: bool,
+    // If any call or kan happened in the kyoku, which breaks the first turn.
+    interrupted: bool,
+    // Hai not allowed to be discarded right after calling.
+    kuikae: Vec<Hai>,
+    events: Vec<Event>,
+}
+
+impl Hanchan {
+    /// Create a hanchan and start east 1.
+    ///
+    /// # Parameters
+    /// * rule: Rules of the hanchan.
+    /// * seed: Any number, deciding yama of every kyoku.
+    pub fn new(rule: Rule, seed: u64) -> Self {
+        let mut hanchan = Self {
+            rule,
+            seed,
+            kyoku_number: 0,
+            points: [rule.start_points; PLAYER_NUMBER],
+            bakaze: Hai::Jihai(1),
+            kyoku: 1,
+            honba: 0,
+            kyoutaku: 0,
+            yama: Yama::new(PLAYER_NUMBER as u8, seed),
+            players: std::array::from_fn(|_| Player::new(vec![])),
+            phase: Phase::Shuukyoku,
+            tsumo_hai: None,
+            rinshan: false,
+            interrupted: false,
+            kuikae: vec![],
+            events: vec![],
+        };
+        hanchan.start_kyoku();
+        hanchan
+    }
+
+    /// Return what the hanchan is waiting for.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Return all events from the start.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Return all players in the current kyoku by seat.
+    pub fn players(&self) -> &[Player; PLAYER_NUMBER] {
+        &self.players
+    }
+
+    /// Return points of all players by seat.
+    pub fn points(&self) -> [i32; PLAYER_NUMBER] {
+        self.points
+    }
+
+    /// Return yama of the current kyoku.
+    pub fn yama(&self) -> &Yama {
+        &self.yama
+    }
+
+    /// Return wind of the round, 1z for east and 2z for south.
+    pub fn bakaze(&self) -> Hai {
+        self.bakaze
+    }
+
+    /// Return the number of kyoku in the round, 1\~4.
+    pub fn kyoku(&self) -> u8 {
+        self.kyoku
+    }
+
+    /// Return the number of honba.
+    pub fn honba(&self) -> u8 {
+        self.honba
+    }
+
+    /// Return the number of riichi sticks on the table.
+    pub fn kyoutaku(&self) -> u8 {
+        self.kyoutaku
+    }
+
+    /// Return seat of oya.
+    pub fn oya(&self) -> usize {
+        self.kyoku as usize - 1
+    }
+
+    /// Return wind of the player of seat, 1z for oya.
+    pub fn jikaze(&self, seat: usize) -> Hai {
+        Hai::Jihai(((seat + PLAYER_NUMBER - self.oya()) % PLAYER_NUMBER) as u8 + 1)
+    }
+
+    /// Take an action expected by `phase`, returning events caused by it, which may go on
+    /// to the next kyoku. Nothing is changed if an error is returned.
+    pub fn step(&mut self, action: Action) -> Result<Vec<Event>, Error> {
+        let events = std::mem::take(&mut self.events);
+        let mut next = self.clone();
+        self.events = events;
+
+        next.apply(action)?;
+        let new_events = std::mem::replace(&mut next.events, std::mem::take(&mut self.events));
+        next.events.extend(new_events.iter().cloned());
+        *self = next;
+        Ok(new_events)
+    }
+
+    fn apply(&mut self, action: Action) -> Result<(), Error> {
+        match (self.phase, action) {
+            (Phase::Dahai { seat }, Action::Dahai { hai, riichi }) => self.dahai(seat, hai, riichi),
+            (Phase::Dahai { seat }, Action::Tsumo) => self.tsumo(seat),
+            (Phase::Dahai { seat }, Action::Ankan(hai)) => self.ankan(seat, hai),
+            (Phase::Dahai { seat }, Action::Kakan(hai)) => self.kakan(seat, hai),
+            (Phase::Naki { seat, hai }, Action::Answer(answers)) => {
+                self.answer(seat, hai, answers, false)
+            }
+            (Phase::Chankan { seat, hai }, Action::Answer(answers)) => {
+                self.answer(seat, hai, answers, true)
+            }
+            _ => Err(Error::WrongPhase),
+        }
+    }
+
+    fn start_kyoku(&mut self) {
+        // Spread seeds of kyoku, so hanchan of nearby seeds do not share yama.
+        let seed = self.seed ^ self.kyoku_number.wrapping_mul(0xD1B54A32D192ED03);
+        self.yama = Yama::new(PLAYER_NUMBER as u8, seed);
+        self.kyoku_number += 1;
+        self.interrupted = false;
+        self.kuikae.clear();
+
+        self.events.push(Event::KyokuStart {
+            bakaze: self.bakaze,
+            kyoku: self.kyoku,
+            honba: self.honba,
+            kyoutaku: self.kyoutaku,
+            dora_hyouji: self.yama.dora_hyouji()[0],
+            points: self.points,
+        });
+        let oya = self.oya();
+        for (index, juntehai) in self.yama.haipai().into_iter().enumerate() {
+            let seat = (oya + index) % PLAYER_NUMBER;
+            self.events.push(Event::Haipai {
+                seat,
+                juntehai: juntehai.clone(),
+            });
+            self.players[seat] = Player::new(juntehai);
+        }
+        self.draw(oya);
+    }
+
+    /// Draw the next hai for the player. Yama must have hai left.
+    fn draw(&mut self, seat: usize) {
+        if let Some(hai) = self.yama.tsumo() {
+            self.take_drawn(seat, hai, false);
+        }
+    }
+
+    fn take_drawn(&mut self, seat: usize, hai: Hai, rinshan: bool) {
+        let juntehai = &mut self.players[seat].tehai.juntehai;
+        juntehai.push(hai);
+        juntehai.sort();
+        self.tsumo_hai = Some(hai);
+        self.rinshan = rinshan;
+        self.phase = Phase::Dahai { seat };
+        self.events.push(Event::Tsumo { seat, hai, rinshan });
+    }
+
+    fn dahai(&mut self, seat: usize, hai: Hai, riichi: bool) -> Result<(), Error> {
+        let player = &self.players[seat];
+        if player.riichi && Some(hai) != self.tsumo_hai {
+            return Err(Error::Tsumogiri(self.tsumo_hai.unwrap_or(hai)));
+        }
+        if self.kuikae.contains(&hai.normalize()) {
+            return Err(Error::Kuikae(hai));
+        }
+        let position = player
+            .tehai
+            .juntehai
+            .iter()
+            .position(|cur| *cur == hai)
+            .ok_or(Error::HaiNotFound { seat, hai })?;
+
+        let player = &mut self.players[seat];
+        player.tehai.juntehai.remove(position);
+        if riichi
+            && (player.riichi
+                || !player.is_menzen()
+                || self.points[seat] < 1000
+                || self.yama.nokori() < PLAYER_NUMBER
+                || !player.is_tenpai()?)
+        {
+            return Err(Error::InvalidRiichi);
+        }
+
+        let tsumogiri = Some(hai) == self.tsumo_hai;
+        player.kawa.push(Sutehai {
+            hai,
+            tsumogiri,
+            riichi,
+            naki: false,
+        });
+        if !riichi {
+            player.ippatsu = false;
+        }
+        if !player.riichi {
+            player.furiten = false;
+        }
+        self.tsumo_hai = None;
+        self.kuikae.clear();
+        self.phase = Phase::Naki { seat, hai };
+        self.events.push(Event::Dahai {
+            seat,
+            hai,
+            tsumogiri,
+            riichi,
+        });
+        Ok(())
+    }
+
+    fn tsumo(&mut self, seat: usize) -> Result<(), Error> {
+        let hai = self.tsumo_hai.ok_or(Error::NotAgari { seat })?;
+        let tensuu = self.tensuu(seat, hai, &self.players[seat].tehai, true, false, true)?;
+        self.agari(vec![(seat, tensuu)], seat, hai);
+        Ok(())
+    }
+
+    fn ankan(&mut self, seat: usize, hai: Hai) -> Result<(), Error> {
+        let hai = hai.normalize();
+        let tsumo_hai = self.tsumo_hai.ok_or(Error::InvalidKan(hai))?;
+        if !self.can_kan() {
+            return Err(Error::InvalidKan(hai));
+        }
+
+        let player = &mut self.players[seat];
+        let machihai = if player.riichi {
+            // Kan in riichi only with the hai just drawn, keeping machihai.
+            if tsumo_hai.normalize() != hai {
+                return Err(Error::InvalidKan(hai));
+            }
+            let mut before = player.clone();
+            let position = before
+                .tehai
+                .juntehai
+                .iter()
+                .position(|cur| *cur == tsumo_hai)
+                .ok_or(Error::InvalidKan(hai))?;
+            before.tehai.juntehai.remove(position);
+            Some(before.machihai()?)
+        } else {
+            None
+        };
+
+        let kantsu: Vec<Hai> = player
+            .tehai
+            .juntehai
+            .iter()
+            .filter(|cur| cur.normalize() == hai)
+            .copied()
+            .collect();
+        let mentsu = Mentsu::new(&kantsu).ok_or(Error::InvalidKan(hai))?;
+        player.tehai.juntehai.retain(|cur| cur.normalize() != hai);
+        let fuuro = Fuuro::ankan(mentsu);
+        player.tehai.fuuro.push(fuuro);
+        if let Some(machihai) = machihai {
+            if player.machihai()? != machihai {
+                return Err(Error::InvalidKan(hai));
+            }
+        }
+
+        self.events.push(Event::Fuuro { seat, fuuro });
+        self.kan(seat);
+        Ok(())
+    }
+
+    fn kakan(&mut self, seat: usize, hai: Hai) -> Result<(), Error> {
+        if self.tsumo_hai.is_none() || !self.can_kan() {
+            return Err(Error::InvalidKan(hai));
+        }
+        let player = &mut self.players[seat];
+        let position = player
+            .tehai
+            .juntehai
+            .iter()
+            .position(|cur| *cur == hai)
+            .ok_or(Error::HaiNotFound { seat, hai })?;
+        let fuuro = player
+            .tehai
+            .fuuro
+            .iter_mut()
+            .find(|fuuro| {
+                fuuro.kind == FuuroKind::Pon
+                    && fuuro.mentsu.normalize() == Mentsu::Koutsu(hai.normalize())
+            })
+            .ok_or(Error::InvalidKan(hai))?;
+
+        let mut kantsu = fuuro.hai_vec();
+        kantsu.push(hai);
+        fuuro.mentsu = Mentsu::new(&kantsu).ok_or(Error::InvalidKan(hai))?;
+        fuuro.kind = FuuroKind::Kakan;
+        let fuuro = *fuuro;
+        player.tehai.juntehai.remove(position);
+
+        self.tsumo_hai = None;
+        self.phase = Phase::Chankan { seat, hai };
+        self.events.push(Event::Fuuro { seat, fuuro });
+        Ok(())
+    }
+
+    /// Resolve answers to a sutehai or a kakan of `seat`.
+    fn answer(
+        &mut self,
+        seat: usize,
+        hai: Hai,
+        answers: [Option<Naki>; PLAYER_NUMBER],
+        chankan: bool,
+    ) -> Result<(), Error> {
+        let mut tensuu_vec: Vec<Option<Tensuu>> = vec![None; PLAYER_NUMBER];
+        let mut fuuro_vec = [None; PLAYER_NUMBER];
+        for offset in 1..PLAYER_NUMBER {
+            let caller = (seat + offset) % PLAYER_NUMBER;
+            let answer = match answers[caller] {
+                Some(answer) => answer,
+                None => continue,
+            };
+            if answer == Naki::Ron {
+                let player = &self.players[caller];
+                if player.is_furiten()? {
+                    return Err(Error::Furiten { seat: caller });
+                }
+                let mut tehai = player.tehai.clone();
+                tehai.juntehai.push(hai);
+                tehai.juntehai.sort();
+                let first = tensuu_vec.iter().all(Option::is_none);
+                let tensuu = self.tensuu(caller, hai, &tehai, false, chankan, first)?;
+                tensuu_vec[caller] = Some(tensuu);
+            } else if chankan {
+                return Err(Error::InvalidNaki { seat: caller });
+            } else {
+                fuuro_vec[caller] = Some(self.naki_fuuro(seat, caller, hai, answer)?);
+            }
+        }
+
+        let resolved = resolve_naki(seat, &answers, &self.rule.naki);
+        if resolved.first().is_some_and(|(_, naki)| *naki == Naki::Ron) {
+            let winners = resolved
+                .into_iter()
+                .filter_map(|(caller, _)| tensuu_vec[caller].take().map(|tensuu| (caller, tensuu)))
+                .collect();
+            self.agari(winners, seat, hai);
+            return Ok(());
+        }
+        let naki = resolved
+            .first()
+            .and_then(|(caller, _)| fuuro_vec[*caller].map(|fuuro| (*caller, fuuro)));
+
+        // Passing a winning hai makes furiten.
+        for offset in 1..PLAYER_NUMBER {
+            let other = (seat + offset) % PLAYER_NUMBER;
+            let mut tehai = self.players[other].tehai.clone();
+            tehai.juntehai.push(hai);
+            tehai.juntehai.sort();
+            if tehai.shanten()? == -1 {
+                self.players[other].furiten = true;
+            }
+        }
+
+        if chankan {
+            self.kan(seat);
+            return Ok(());
+        }
+
+        let player = &mut self.players[seat];
+        if player.kawa.last().is_some_and(|sutehai| sutehai.riichi) {
+            player.riichi = true;
+            player.ippatsu = true;
+            player.double_riichi = player.kawa.len() == 1 && !self.interrupted;
+            self.points[seat] -= 1000;
+            self.kyoutaku += 1;
+            self.events.push(Event::Riichi { seat });
+        }
+
+        match naki {
+            Some((caller, fuuro)) => {
+                if let Some(sutehai) = self.players[seat].kawa.last_mut() {
+                    sutehai.naki = true;
+                }
+                let player = &mut self.players[caller];
+                let mut hai_vec = fuuro.hai_vec();
+                let position = hai_vec
+                    .iter()
+                    .position(|cur| *cur == hai)
+                    .ok_or(Error::InvalidNaki { seat: caller })?;
+                hai_vec.remove(position);
+                for cur in hai_vec {
+                    let position = player
+                        .tehai
+                        .juntehai
+                        .iter()
+                        .position(|juntehai_hai| *juntehai_hai == cur)
+                        .ok_or(Error::HaiNotFound {
+                            seat: caller,
+                            hai: cur,
+                        })?;
+                    player.tehai.juntehai.remove(position);
+                }
+                player.tehai.fuuro.push(fuuro);
+                self.events.push(Event::Fuuro {
+                    seat: caller,
+                    fuuro,
+                });
+
+                if fuuro.kind == FuuroKind::Daiminkan {
+                    self.kan(caller);
+                } else {
+                    self.interrupt();
+                    self.kuikae = kuikae(&fuuro, hai);
+                    self.phase = Phase::Dahai { seat: caller };
+                }
+            }
+            None if self.yama.nokori() == 0 => self.ryuukyoku()?,
+            None => self.draw((seat + 1) % PLAYER_NUMBER),
+        }
+        Ok(())
+    }
+
+    /// Check a call other than ron, returning the fuuro made.
+    fn naki_fuuro(&self, seat: usize, caller: usize, hai: Hai, naki: Naki) -> Result<Fuuro, Error> {
+        let invalid = Error::InvalidNaki { seat: caller };
+        let player = &self.players[caller];
+        if player.riichi || self.yama.nokori() == 0 {
+            return Err(invalid);
+        }
+        let naki = match naki {
+            Naki::Chii(first, second) => Naki::Chii(first.min(second), first.max(second)),
+            Naki::Pon(first, second) => Naki::Pon(first.min(second), first.max(second)),
+            _ => naki,
+        };
+        let from = tacha(caller, seat);
+        if !naki_candidates(&player.tehai, hai, from)?.contains(&naki) {
+            return Err(invalid);
+        }
+
+        let (kind, mut hai_vec) = match naki {
+            Naki::Chii(first, second) => (FuuroKind::Chii, vec![first, second]),
+            Naki::Pon(first, second) => (FuuroKind::Pon, vec![first, second]),
+            Naki::Daiminkan if self.can_kan() => {
+                let hai_vec = player
+                    .tehai
+                    .juntehai
+                    .iter()
+                    .filter(|cur| cur.normalize() == hai.normalize())
+                    .copied()
+                    .collect();
+                (FuuroKind::Daiminkan, hai_vec)
+            }
+            _ => return Err(invalid),
+        };
+        hai_vec.push(hai);
+        let mentsu = Mentsu::new(&hai_vec).ok_or(invalid)?;
+        Ok(Fuuro {
+            kind,
+            mentsu,
+            called: Some(hai),
+            from: Some(from),
+        })
+    }
+
+    /// Return if one more kan is allowed.
+    fn can_kan(&self) -> bool {
+        let kan_number = self
+            .players
+            .iter()
+            .flat_map(|player| &player.tehai.fuuro)
+            .filter(|fuuro| matches!(fuuro.mentsu, Mentsu::Kantsu(_)))
+            .count();
+        kan_number < MAX_KAN_NUMBER && self.yama.nokori() > 0
+    }
+
+    /// Reveal kandora and draw rinshanpai after kan.
+    fn kan(&mut self, seat: usize) {
+        self.interrupt();
+        if let Some(hai) = self.yama.kandora() {
+            self.events.push(Event::Kandora { hai });
+        }
+        if let Some(hai) = self.yama.rinshan() {
+            self.take_drawn(seat, hai, true);
+        }
+    }
+
+    /// Any call or kan breaks ippatsu and the first turn.
+    fn interrupt(&mut self) {
+        self.interrupted = true;
+        for player in self.players.iter_mut() {
+            player.ippatsu = false;
+        }
+    }
+
+    /// Calculate points of winning with `tehai` including agari hai, with honba and
+    /// kyoutaku only for the `first` winner.
+    fn tensuu(
+        &self,
+        seat: usize,
+        hai: Hai,
+        tehai: &crate::calculator::Tehai,
+        tsumo: bool,
+        chankan: bool,
+        first: bool,
+    ) -> Result<Tensuu, Error> {
+        let player = &self.players[seat];
+        let mut condition = AgariCondition::new(hai, tsumo);
+        condition.riichi = player.riichi && !player.double_riichi;
+        condition.double_riichi = player.double_riichi;
+        condition.ippatsu = player.ippatsu;
+        condition.haitei = self.yama.is_haitei() && !self.rinshan;
+        condition.rinshan = tsumo && self.rinshan;
+        condition.chankan = chankan;
+        condition.tenhou = tsumo && player.kawa.is_empty() && !self.interrupted;
+        condition.bakaze = self.bakaze;
+        condition.jikaze = self.jikaze(seat);
+        condition.dora_hyouji = self.yama.dora_hyouji();
+        condition.uradora_hyouji = self.yama.uradora_hyouji();
+        if first {
+            condition.honba = self.honba;
+            condition.kyoutaku = self.kyoutaku;
+        }
+
+        match tehai.tensuu(&condition) {
+            Ok(Some(tensuu)) => Ok(tensuu),
+            Ok(None) => Err(Error::NoYaku { seat }),
+            Err(crate::calculator::Error::NotAgari) => Err(Error::NotAgari { seat }),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Pay points for agari of all winners, then go on.
+    fn agari(&mut self, winners: Vec<(usize, Tensuu)>, from: usize, hai: Hai) {
+        let renchan = winners.iter().any(|(seat, _)| *seat == self.oya());
+        for (seat, tensuu) in winners {
+            let mut delta = [0; PLAYER_NUMBER];
+            delta[seat] += tensuu.kyoutaku as i32;
+            for other in (0..PLAYER_NUMBER).filter(|other| *other != seat) {
+                let payment = match tensuu.shiharai {
+                    Shiharai::Ron(points) if other == from => points,
+                    Shiharai::Ron(_) => 0,
+                    Shiharai::TsumoOya(points) => points,
+                    Shiharai::TsumoKo { oya, .. } if other == self.oya() => oya,
+                    Shiharai::TsumoKo { ko, .. } => ko,
+                } as i32;
+                delta[other] -= payment;
+                delta[seat] += payment;
+            }
+            self.kyoutaku = 0;
+            self.pay(delta);
+            self.events.push(Event::Agari {
+                seat,
+                from,
+                hai,
+                tensuu: Box::new(tensuu),
+                delta,
+            });
+        }
+
+        if renchan {
+            self.honba += 1;
+        } else {
+            self.honba = 0;
+        }
+        self.end_kyoku(renchan);
+    }
+
+    /// No hai left to draw, settled by `Ryuukyoku`.
+    fn ryuukyoku(&mut self) -> Result<(), Error> {
+        let ryuukyoku = Ryuukyoku::new(&self.players, self.oya())?;
+        let delta = std::array::from_fn(|seat| ryuukyoku.delta[seat]);
+        self.pay(delta);
+        self.events.push(Event::Ryuukyoku {
+            tenpai: std::array::from_fn(|seat| ryuukyoku.tenpai[seat]),
+            nagashi_mangan: std::array::from_fn(|seat| ryuukyoku.nagashi_mangan[seat]),
+            delta,
+        });
+
+        self.honba += 1;
+        self.end_kyoku(ryuukyoku.is_renchan(self.oya()));
+        Ok(())
+    }
+
+    fn pay(&mut self, delta: [i32; PLAYER_NUMBER]) {
+        for (points, delta) in self.points.iter_mut().zip(delta) {
+            *points += delta;
+        }
+    }
+
+    /// Go on to the next kyoku, or end the hanchan.
+    fn end_kyoku(&mut self, renchan: bool) {
+        let last = self.bakaze == Hai::Jihai(2) && self.kyoku as usize == PLAYER_NUMBER;
+        let tobi = self.rule.tobi && self.points.iter().any(|points| *points < 0);
+        let agari_yame = self.rule.agari_yame && self.top() == self.oya();
+        if tobi || (last && (!renchan || agari_yame)) {
+            let top = self.top();
+            self.points[top] += 1000 * self.kyoutaku as i32;
+            self.kyoutaku = 0;
+            self.phase = Phase::Shuukyoku;
+            self.events.push(Event::Shuukyoku {
+                points: self.points,
+            });
+            return;
+        }
+
+        if !renchan {
+            if self.kyoku as usize == PLAYER_NUMBER {
+                self.kyoku = 1;
+                self.bakaze = Hai::Jihai(2);
+            } else {
+                self.kyoku += 1;
+            }
+        }
+        self.start_kyoku();
+    }
+
+    /// Return seat of the top player, the one nearer to seat 0 if the same points.
+    fn top(&self) -> usize {
+        (0..PLAYER_NUMBER)
+            .rev()
+            .max_by_key(|seat| self.points[*seat])
+            .unwrap_or(0)
+    }
+}
+
+/// Return who `from` is relative to `seat`.
+fn tacha(seat: usize, from: usize) -> Tacha {
+    match (from + PLAYER_NUMBER - seat) % PLAYER_NUMBER {
+        1 => Tacha::Shimocha,
+        2 => Tacha::Toimen,
+        _ => Tacha::Kamicha,
+    }
+}
+
+/// Return hai not allowed to be discarded after calling `hai` into `fuuro`, which are the
+/// same hai and the other end of juntsu, like 4m and 1m for 23m calling 4m.
+fn kuikae(fuuro: &Fuuro, hai: Hai) -> Vec<Hai> {
+    let hai = hai.normalize();
+    let mut kuikae = vec![hai];
+    if let Mentsu::Juntsu(first, _, third) = fuuro.mentsu.normalize() {
+        if hai == first {
+            kuikae.extend(third.next(4, false));
+        } else if hai == third {
+            kuikae.extend(first.previous(4, false));
+        }
+    }
+    kuikae
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::Yaku;
+    use crate::game::player::tests::player;
+
+    /// Play with simple players, who win whenever possible, discard to reduce shanten,
+    /// riichi when tenpai, and pon only sangenpai. Stop when shuukyoku or `kyoku_number`
+    /// kyoku are over.
+    fn play(hanchan: &mut Hanchan, kyoku_number: usize) {
+        let started = |hanchan: &Hanchan| {
+            hanchan
+                .events()
+                .iter()
+                .filter(|event| matches!(event, Event::KyokuStart { .. }))
+                .count()
+        };
+        let mut step_number = 0;
+        while hanchan.phase() != Phase::Shuukyoku && started(hanchan) <= kyoku_number {
+            step_number += 1;
+            assert!(step_number < 10000);
+
+            match hanchan.phase() {
+                Phase::Dahai { seat } => {
+                    let tehai = &hanchan.players()[seat].tehai;
+                    if tehai.shanten().unwrap() == -1 && hanchan.step(Action::Tsumo).is_ok() {
+                        continue;
+                    }
+                    let player = &hanchan.players()[seat];
+                    let mut choices: Vec<(i32, Hai)> = (0..player.tehai.juntehai.len())
+                        .map(|index| {
+                            let mut tehai = player.tehai.clone();
+                            let hai = tehai.juntehai.remove(index);
+                            (tehai.shanten().unwrap(), hai)
+                        })
+                        .collect();
+                    choices.sort_by_key(|(shanten, _)| *shanten);
+                    if player.riichi {
+                        choices = vec![(0, hanchan.tsumo_hai.unwrap())];
+                    }
+                    let discarded = choices.into_iter().any(|(shanten, hai)| {
+                        (shanten == 0 && hanchan.step(Action::Dahai { hai, riichi: true }).is_ok())
+                            || hanchan.step(Action::Dahai { hai, riichi: false }).is_ok()
+                    });
+                    assert!(discarded);
+                }
+                Phase::Naki { seat, hai } | Phase::Chankan { seat, hai } => {
+                    let mut answers = [None; PLAYER_NUMBER];
+                    for offset in 1..PLAYER_NUMBER {
+                        let other = (seat + offset) % PLAYER_NUMBER;
+                        let tehai = &hanchan.players()[other].tehai;
+                        let candidates = naki_candidates(tehai, hai, tacha(other, seat)).unwrap();
+                        answers[other] = candidates
+                            .into_iter()
+                            .filter(|naki| {
+                                *naki == Naki::Ron
+                                    || (hai.is_sangenpai() && matches!(naki, Naki::Pon(..)))
+                            })
+                            .find(|naki| {
+                                let mut single = [None; PLAYER_NUMBER];
+                                single[other] = Some(*naki);
+                                hanchan.clone().step(Action::Answer(single)).is_ok()
+                            });
+                    }
+                    hanchan.step(Action::Answer(answers)).unwrap();
+                }
+                Phase::Shuukyoku => unreachable!(),
+            }
+
+            let total: i32 = hanchan.points().iter().sum();
+            assert_eq!(total + 1000 * hanchan.kyoutaku() as i32, 100000);
+        }
+    }
+
+    #[test]
+    fn play_kyoku() {
+        let daburon = Rule {
+            naki: NakiRule { atamahane: false },
+            ..Rule::default()
+        };
+        for (rule, seed) in [(Rule::default(), 0), (daburon, 1)] {
+            let mut hanchan = Hanchan::new(rule, seed);
+            play(&mut hanchan, 1);
+            let total: i32 = hanchan.points().iter().sum();
+            assert_eq!(total + 1000 * hanchan.kyoutaku() as i32, 100000);
+
+            let mut again = Hanchan::new(rule, seed);
+            play(&mut again, 1);
+            assert_eq!(again.events(), hanchan.events());
+        }
+    }
+
+    /// Play whole hanchan, which takes a while without optimization.
+    #[test]
+    #[ignore]
+    fn play_hanchan() {
+        let daburon = Rule {
+            naki: NakiRule { atamahane: false },
+            ..Rule::default()
+        };
+        for (rule, seed) in [(Rule::default(), 0), (daburon, 1)] {
+            let mut hanchan = Hanchan::new(rule, seed);
+            play(&mut hanchan, usize::MAX);
+
+            let events = hanchan.events();
+            let kyoku_number = events
+                .iter()
+                .filter(|event| matches!(event, Event::KyokuStart { .. }))
+                .count();
+            assert!(kyoku_number >= 8 || hanchan.points().iter().any(|points| *points < 0));
+            assert!(matches!(events.last(), Some(Event::Shuukyoku { .. })));
+            assert_eq!(hanchan.points().iter().sum::<i32>(), 100000);
+        }
+    }
+
+    #[test]
+    fn wrong_action() {
+        let mut hanchan = Hanchan::new(Rule::default(), 1);
+        assert_eq!(hanchan.phase(), Phase::Dahai { seat: 0 });
+        let events = hanchan.events().to_vec();
+        assert_eq!(events.len(), 6);
+        assert_eq!(
+            hanchan.step(Action::Answer([None; PLAYER_NUMBER])),
+            Err(Error::WrongPhase)
+        );
+
+        let juntehai = hanchan.players()[0].tehai.juntehai.clone();
+        let missing = Hai::all_type(4)
+            .into_iter()
+            .find(|hai| !juntehai.contains(hai))
+            .unwrap();
+        assert_eq!(
+            hanchan.step(Action::Dahai {
+                hai: missing,
+                riichi: false
+            }),
+            Err(Error::HaiNotFound {
+                seat: 0,
+                hai: missing
+            })
+        );
+        assert_eq!(hanchan.events(), events);
+
+        let hai = juntehai[0];
+        let new_events = hanchan.step(Action::Dahai { hai, riichi: false }).unwrap();
+        assert_eq!(
+            new_events,
+            [Event::Dahai {
+                seat: 0,
+                hai,
+                tsumogiri: Some(hai)
+                    == events.last().and_then(|event| match event {
+                        Event::Tsumo { hai, .. } => Some(*hai),
+                        _ => None,
+                    }),
+                riichi: false
+            }]
+        );
+        assert_eq!(hanchan.phase(), Phase::Naki { seat: 0, hai });
+        assert_eq!(hanchan.players()[0].tehai.juntehai.len(), 13);
+
+        let new_events = hanchan.step(Action::Answer([None; PLAYER_NUMBER])).unwrap();
+        assert!(matches!(new_events[..], [Event::Tsumo { seat: 1, .. }]));
+    }
+
+    #[test]
+    fn ankan_in_riichi() {
+        let mut hanchan = Hanchan::new(Rule::default(), 0);
+        // Waiting for 4m, 6m and 7m, but only for 6m after ankan of 5m.
+        hanchan.players[0] = Player {
+            riichi: true,
+            ..player("55556m123p456p789s", &[])
+        };
+        hanchan.tsumo_hai = Some(Hai::Manzu(5));
+        assert_eq!(
+            hanchan.step(Action::Ankan(Hai::Manzu(5))),
+            Err(Error::InvalidKan(Hai::Manzu(5)))
+        );
+        assert_eq!(hanchan.players()[0].tehai.juntehai.len(), 14);
+
+        // Waiting for 2z both before and after ankan.
+        hanchan.players[0] = Player {
+            riichi: true,
+            ..player("1111m234p456p789s2z", &[])
+        };
+        hanchan.tsumo_hai = Some(Hai::Manzu(1));
+        let events = hanchan.step(Action::Ankan(Hai::Manzu(1))).unwrap();
+        assert!(matches!(
+            events[0],
+            Event::Fuuro { seat: 0, fuuro } if fuuro.kind == FuuroKind::Ankan
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(Event::Tsumo {
+                seat: 0,
+                rinshan: true,
+                ..
+            })
+        ));
+        assert!(hanchan.players()[0].riichi);
+    }
+
+    /// Tehai of 13 hai far from tenpai, which neither wins nor calls.
+    const NOTEN: &str = "147m258p369s1234z";
+
+    /// Create a hanchan with tehai of 13 hai for each seat, where `seat` has just drawn
+    /// `hai`.
+    fn hanchan(rule: Rule, tehai: [&str; PLAYER_NUMBER], seat: usize, hai: Hai) -> Hanchan {
+        let mut hanchan = Hanchan::new(rule, 0);
+        hanchan.players = std::array::from_fn(|index| player(tehai[index], &[]));
+        hanchan.take_drawn(seat, hai, false);
+        hanchan
+    }
+
+    /// Return answers where only `seat` calls.
+    fn answer(seat: usize, naki: Naki) -> Action {
+        let mut answers = [None; PLAYER_NUMBER];
+        answers[seat] = Some(naki);
+        Action::Answer(answers)
+    }
+
+    fn dahai(hai: Hai) -> Action {
+        Action::Dahai { hai, riichi: false }
+    }
+
+    fn riichi(hai: Hai) -> Action {
+        Action::Dahai { hai, riichi: true }
+    }
+
+    #[test]
+    fn riichi_declaration() {
+        let tenpai = "123m456p789s1122z";
+        let new = || {
+            hanchan(
+                Rule::default(),
+                [tenpai, NOTEN, NOTEN, NOTEN],
+                0,
+                Hai::Jihai(3),
+            )
+        };
+
+        // Not tenpai after the sutehai.
+        let mut hanchan = new();
+        assert_eq!(
+            hanchan.step(riichi(Hai::Jihai(1))),
+            Err(Error::InvalidRiichi)
+        );
+
+        // Not menzen.
+        hanchan.players[0] = player("123m456p1122z[<789s]", &[]);
+        hanchan.take_drawn(0, Hai::Jihai(3), false);
+        assert_eq!(
+            hanchan.step(riichi(Hai::Jihai(3))),
+            Err(Error::InvalidRiichi)
+        );
+
+        // Points below 1000.
+        let mut hanchan = new();
+        hanchan.points[0] = 900;
+        assert_eq!(
+            hanchan.step(riichi(Hai::Jihai(3))),
+            Err(Error::InvalidRiichi)
+        );
+
+        // Less than 4 hai left to draw.
+        let mut hanchan = new();
+        while hanchan.yama.nokori() >= PLAYER_NUMBER {
+            hanchan.yama.tsumo();
+        }
+        assert_eq!(
+            hanchan.step(riichi(Hai::Jihai(3))),
+            Err(Error::InvalidRiichi)
+        );
+
+        // Accepted after nobody calls the sutehai.
+        let mut hanchan = new();
+        let events = hanchan.step(riichi(Hai::Jihai(3))).unwrap();
+        assert_eq!(
+            events,
+            [Event::Dahai {
+                seat: 0,
+                hai: Hai::Jihai(3),
+                tsumogiri: true,
+                riichi: true
+            }]
+        );
+        assert!(!hanchan.players()[0].riichi);
+        let events = hanchan.step(Action::Answer([None; PLAYER_NUMBER])).unwrap();
+        assert_eq!(events[0], Event::Riichi { seat: 0 });
+        assert!(matches!(events[1], Event::Tsumo { seat: 1, .. }));
+        let player = &hanchan.players()[0];
+        assert!(player.riichi && player.double_riichi && player.ippatsu);
+        assert_eq!(hanchan.points()[0], 24000);
+        assert_eq!(hanchan.kyoutaku(), 1);
+
+        // Only the hai just drawn is discarded in riichi, and no riichi again.
+        hanchan.take_drawn(0, Hai::Manzu(5), false);
+        assert_eq!(
+            hanchan.step(dahai(Hai::Jihai(1))),
+            Err(Error::Tsumogiri(Hai::Manzu(5)))
+        );
+        assert_eq!(
+            hanchan.step(riichi(Hai::Manzu(5))),
+            Err(Error::InvalidRiichi)
+        );
+        assert!(hanchan.step(dahai(Hai::Manzu(5))).is_ok());
+    }
+
+    #[test]
+    fn kuikae_after_chii() {
+        let tehai = [NOTEN, "12344m456p789s11z", NOTEN, NOTEN];
+        let mut hanchan = hanchan(Rule::default(), tehai, 0, Hai::Manzu(4));
+        hanchan.step(dahai(Hai::Manzu(4))).unwrap();
+        let chii = Naki::Chii(Hai::Manzu(2), Hai::Manzu(3));
+        let events = hanchan.step(answer(1, chii)).unwrap();
+        assert!(matches!(
+            events[..],
+            [Event::Fuuro { seat: 1, fuuro }] if fuuro.kind == FuuroKind::Chii
+        ));
+        assert_eq!(hanchan.phase(), Phase::Dahai { seat: 1 });
+
+        // The same hai and the other end of the juntsu.
+        for hai in [Hai::Manzu(4), Hai::Manzu(1)] {
+            assert_eq!(hanchan.step(dahai(hai)), Err(Error::Kuikae(hai)));
+        }
+        assert!(hanchan.step(dahai(Hai::Jihai(1))).is_ok());
+    }
+
+    #[test]
+    fn kakan_and_chankan() {
+        let tehai = ["123m456m789s1z[^555p]", NOTEN, "46p234m456m55678s", NOTEN];
+        let mut hanchan = hanchan(Rule::default(), tehai, 0, Hai::Pinzu(5));
+        let events = hanchan.step(Action::Kakan(Hai::Pinzu(5))).unwrap();
+        assert!(matches!(
+            events[..],
+            [Event::Fuuro { seat: 0, fuuro }] if fuuro.kind == FuuroKind::Kakan
+        ));
+        assert_eq!(
+            hanchan.phase(),
+            Phase::Chankan {
+                seat: 0,
+                hai: Hai::Pinzu(5)
+            }
+        );
+
+        // Only ron is allowed on kakan.
+        let pon = Naki::Pon(Hai::Pinzu(5), Hai::Pinzu(5));
+        assert_eq!(
+            hanchan.step(answer(1, pon)),
+            Err(Error::InvalidNaki { seat: 1 })
+        );
+
+        let mut ron = hanchan.clone();
+        let events = ron.step(answer(2, Naki::Ron)).unwrap();
+        match &events[0] {
+            Event::Agari {
+                seat: 2,
+                from: 0,
+                tensuu,
+                ..
+            } => assert!(tensuu.yaku_vec.contains(&Yaku::Chankan)),
+            event => panic!("{:?}", event),
+        }
+
+        // Kandora and rinshan after nobody wins.
+        let events = hanchan.step(Action::Answer([None; PLAYER_NUMBER])).unwrap();
+        assert!(matches!(
+            events[..],
+            [
+                Event::Kandora { .. },
+                Event::Tsumo {
+                    seat: 0,
+                    rinshan: true,
+                    ..
+                }
+            ]
+        ));
+        assert!(hanchan.players()[2].furiten);
+    }
+
+    #[test]
+    fn furiten() {
+        let tehai = [NOTEN, NOTEN, "46p234m456m55678s", NOTEN];
+        let pinzu = Hai::Pinzu(5);
+
+        // Passing a winning hai makes furiten until the next sutehai.
+        let mut hanchan = hanchan(Rule::default(), tehai, 0, pinzu);
+        hanchan.step(dahai(pinzu)).unwrap();
+        hanchan.step(Action::Answer([None; PLAYER_NUMBER])).unwrap();
+        assert!(hanchan.players()[2].furiten);
+        hanchan.take_drawn(1, pinzu, false);
+        hanchan.step(dahai(pinzu)).unwrap();
+        assert_eq!(
+            hanchan.step(answer(2, Naki::Ron)),
+            Err(Error::Furiten { seat: 2 })
+        );
+
+        hanchan.take_drawn(2, Hai::Jihai(1), false);
+        hanchan.step(dahai(Hai::Jihai(1))).unwrap();
+        assert!(!hanchan.players()[2].furiten);
+        hanchan.take_drawn(3, pinzu, false);
+        hanchan.step(dahai(pinzu)).unwrap();
+        let events = hanchan.step(answer(2, Naki::Ron)).unwrap();
+        assert!(matches!(
+            events[0],
+            Event::Agari {
+                seat: 2,
+                from: 3,
+                ..
+            }
+        ));
+
+        // Furiten in riichi lasts after the sutehai.
+        let mut hanchan = self::hanchan(Rule::default(), tehai, 0, pinzu);
+        hanchan.players[2].riichi = true;
+        hanchan.step(dahai(pinzu)).unwrap();
+        hanchan.step(Action::Answer([None; PLAYER_NUMBER])).unwrap();
+        hanchan.take_drawn(2, Hai::Jihai(1), false);
+        hanchan.step(dahai(Hai::Jihai(1))).unwrap();
+        assert!(hanchan.players()[2].furiten);
+        hanchan.take_drawn(3, pinzu, false);
+        hanchan.step(dahai(pinzu)).unwrap();
+        assert_eq!(
+            hanchan.step(answer(2, Naki::Ron)),
+            Err(Error::Furiten { seat: 2 })
+        );
+
+        // A winning hai in kawa.
+        let mut hanchan = self::hanchan(Rule::default(), tehai, 0, pinzu);
+        hanchan.players[2] = player(tehai[2], &[pinzu]);
+        hanchan.step(dahai(pinzu)).unwrap();
+        assert_eq!(
+            hanchan.step(answer(2, Naki::Ron)),
+            Err(Error::Furiten { seat: 2 })
+        );
+    }
+
+    #[test]
+    fn honba_and_renchan() {
+        let tenpai = "123m456p789s1122z";
+        let tehai = [tenpai, NOTEN, NOTEN, NOTEN];
+
+        // Oya renchan by tsumo, after the first turn not to be tenhou.
+        let mut hanchan = hanchan(Rule::default(), tehai, 0, Hai::Jihai(1));
+        hanchan.interrupted = true;
+        hanchan.step(Action::Tsumo).unwrap();
+        assert_eq!((hanchan.kyoku(), hanchan.honba(), hanchan.oya()), (1, 1, 0));
+
+        // Honba is reset by agari of ko.
+        hanchan.players[1] = player(tenpai, &[]);
+        hanchan.take_drawn(1, Hai::Jihai(2), false);
+        hanchan.interrupted = true;
+        hanchan.step(Action::Tsumo).unwrap();
+        assert_eq!((hanchan.kyoku(), hanchan.honba(), hanchan.oya()), (2, 0, 1));
+
+        // Oya renchan by tenpai at ryuukyoku.
+        hanchan.players = std::array::from_fn(|seat| player([NOTEN, tenpai][seat % 2], &[]));
+        hanchan.players[3] = player(NOTEN, &[]);
+        hanchan.take_drawn(1, Hai::Manzu(5), false);
+        while hanchan.yama.tsumo().is_some() {}
+        hanchan.step(dahai(Hai::Manzu(5))).unwrap();
+        let events = hanchan.step(Action::Answer([None; PLAYER_NUMBER])).unwrap();
+        assert_eq!(
+            events[0],
+            Event::Ryuukyoku {
+                tenpai: [false, true, false, false],
+                nagashi_mangan: [false; PLAYER_NUMBER],
+                delta: [-1000, 3000, -1000, -1000]
+            }
+        );
+        assert_eq!((hanchan.kyoku(), hanchan.honba(), hanchan.oya()), (2, 1, 1));
+    }
+
+    #[test]
+    fn double_ron() {
+        let rule = Rule {
+            naki: NakiRule { atamahane: false },
+            ..Rule::default()
+        };
+        let tenpai = "46p234m456m55678s";
+        let pinzu = Hai::Pinzu(5);
+        let mut hanchan = hanchan(rule, [NOTEN, tenpai, tenpai, NOTEN], 0, pinzu);
+        hanchan.honba = 2;
+        hanchan.kyoutaku = 1;
+        hanchan.step(dahai(pinzu)).unwrap();
+
+        // Points without honba and kyoutaku.
+        let ron = |seat: usize| {
+            let mut tehai = hanchan.players[seat].tehai.clone();
+            tehai.juntehai.push(pinzu);
+            tehai.juntehai.sort();
+            match hanchan.tensuu(seat, pinzu, &tehai, false, false, false) {
+                Ok(Tensuu {
+                    shiharai: Shiharai::Ron(points),
+                    ..
+                }) => points,
+                result => panic!("{:?}", result),
+            }
+        };
+        let expected = [(1, ron(1) + 600, 1000), (2, ron(2), 0)];
+
+        let mut answers = [None; PLAYER_NUMBER];
+        answers[1] = Some(Naki::Ron);
+        answers[2] = Some(Naki::Ron);
+        let events = hanchan.step(Action::Answer(answers)).unwrap();
+        for (event, (seat, points, kyoutaku)) in events.iter().zip(expected) {
+            match event {
+                Event::Agari {
+                    seat: winner,
+                    from: 0,
+                    tensuu,
+                    delta,
+                    ..
+                } => {
+                    assert_eq!(*winner, seat);
+                    assert_eq!(tensuu.shiharai, Shiharai::Ron(points));
+                    assert_eq!(tensuu.kyoutaku, kyoutaku);
+                    assert_eq!(delta[seat], (points + kyoutaku) as i32);
+                }
+                event => panic!("{:?}", event),
+            }
+        }
+        assert_eq!(
+            (hanchan.kyoku(), hanchan.honba(), hanchan.kyoutaku()),
+            (2, 0, 0)
+        );
+    }
+
+    #[test]
+    fn agari_yame() {
+        let tehai = [NOTEN, NOTEN, NOTEN, "123m456p789s1122z"];
+        let south_4 = |rule: Rule, points: [i32; PLAYER_NUMBER]| {
+            let mut hanchan = hanchan(rule, tehai, 3, Hai::Jihai(1));
+            hanchan.bakaze = Hai::Jihai(2);
+            hanchan.kyoku = 4;
+            hanchan.points = points;
+            hanchan.interrupted = true;
+            hanchan.step(Action::Tsumo).unwrap();
+            hanchan
+        };
+        let top = [20000, 20000, 20000, 40000];
+
+        let hanchan = south_4(Rule::default(), top);
+        assert_eq!(hanchan.phase(), Phase::Shuukyoku);
+        assert!(matches!(
+            hanchan.events().last(),
+            Some(Event::Shuukyoku { .. })
+        ));
+
+        // Renchan unless the rule, or oya is not the top.
+        let rule = Rule {
+            agari_yame: false,
+            ..Rule::default()
+        };
+        for hanchan in [
+            south_4(rule, top),
+            south_4(Rule::default(), [50000, 15000, 15000, 20000]),
+        ] {
+            assert_ne!(hanchan.phase(), Phase::Shuukyoku);
+            assert_eq!((hanchan.kyoku(), hanchan.honba()), (4, 1));
+        }
+    }
+
+    #[test]
+    fn tobi() {
+        let tehai = ["123m456p789s1122z", NOTEN, NOTEN, NOTEN];
+        let east_1 = |rule: Rule| {
+            let mut hanchan = hanchan(rule, tehai, 0, Hai::Jihai(1));
+            hanchan.points = [25000, 1000, 25000, 49000];
+            hanchan.interrupted = true;
+            hanchan.step(Action::Tsumo).unwrap();
+            hanchan
+        };
+
+        let hanchan = east_1(Rule::default());
+        assert!(hanchan.points()[1] < 0);
+        assert_eq!(hanchan.phase(), Phase::Shuukyoku);
+
+        let rule = Rule {
+            tobi: false,
+            ..Rule::default()
+        };
+        let hanchan = east_1(rule);
+        assert_ne!(hanchan.phase(), Phase::Shuukyoku);
+        assert_eq!((hanchan.kyoku(), hanchan.honba()), (1, 1));
+    }
+
+    #[test]
+    fn kyoutaku_to_top() {
+        let mut hanchan = hanchan(Rule::default(), [NOTEN; PLAYER_NUMBER], 3, Hai::Manzu(5));
+        hanchan.bakaze = Hai::Jihai(2);
+        hanchan.kyoku = 4;
+        hanchan.kyoutaku = 2;
+        hanchan.points = [30000, 20000, 22000, 26000];
+        while hanchan.yama.tsumo().is_some() {}
+        hanchan.step(dahai(Hai::Manzu(5))).unwrap();
+        let events = hanchan.step(Action::Answer([None; PLAYER_NUMBER])).unwrap();
+        assert!(matches!(
+            events[..],
+            [Event::Ryuukyoku { delta, .. }, Event::Shuukyoku { points }]
+                if delta == [0; PLAYER_NUMBER] && points == [32000, 20000, 22000, 26000]
+        ));
+        assert_eq!(hanchan.kyoutaku(), 0);
+    }
+}
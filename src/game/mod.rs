@@ -0,0 +1,15 @@
+mod error;
+mod event;
+mod hanchan;
+mod naki;
+mod player;
+mod ryuukyoku;
+mod tochuu;
+
+pub use error::Error;
+pub use event::{Action, Event, Naki, Phase};
+pub use hanchan::{Hanchan, Rule};
+pub use naki::{naki_candidates, resolve_naki, NakiRule};
+pub use player::{Player, Sutehai};
+pub use ryuukyoku::Ryuukyoku;
+pub use tochuu::{TochuuRule, TochuuRyuukyoku};
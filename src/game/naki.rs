@@ -0,0 +1,239 @@
+use super::{Error, Naki};
+use crate::calculator::{self, Hai, Tacha, Tehai};
+
+/// Rules of resolving calls on the same hai.
+///
+/// # Japanese
+/// * atamahane: 頭ハネ
+///
+/// # Member
+/// * atamahane: only the first player in turn order from the discarder wins when more than
+///   one player ron. Otherwise all of them win.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NakiRule {
+    pub atamahane: bool,
+}
+
+impl Default for NakiRule {
+    fn default() -> Self {
+        Self { atamahane: true }
+    }
+}
+
+/// Return calls allowed by the tehai shape only, of a 3*k+1 tehai on `hai` from `from`.
+///
+/// Nothing about the game is checked, neither yaku, furiten, riichi, kuikae after the call,
+/// the fifth kan nor the last hai, which are left to `Hanchan`. Ron is listed when the
+/// tehai is complete with the hai.
+/// Chii is only from kamicha, and not at all on 3-players mode. Every way of making the
+/// mentsu is listed, like `Chii(3m, 4m)` and `Chii(4m, 6m)` for 5m, and `Pon(5m, 5m)` and
+/// `Pon(5m, 0m)` when red five can be used or not. The two hai are sorted.
+pub fn naki_candidates(tehai: &Tehai, hai: Hai, from: Tacha) -> Result<Vec<Naki>, Error> {
+    let juntehai_number = tehai.juntehai.len();
+    if juntehai_number % 3 != 1 {
+        return Err(calculator::Error::WrongHaiNumber {
+            remainder: 1,
+            found: juntehai_number,
+        }
+        .into());
+    }
+
+    let mut candidates = vec![];
+    let mut agari = tehai.clone();
+    agari.juntehai.push(hai);
+    agari.juntehai.sort();
+    if agari.shanten()? == -1 {
+        candidates.push(Naki::Ron);
+    }
+
+    // Hai in juntehai of the same kind, red five apart.
+    let same_kind = |target: Hai| {
+        let mut hai_vec: Vec<Hai> = tehai
+            .juntehai
+            .iter()
+            .filter(|cur| cur.normalize() == target)
+            .copied()
+            .collect();
+        hai_vec.sort();
+        hai_vec
+    };
+
+    let player_number = tehai.player_number;
+    let hai = hai.normalize();
+    if from == Tacha::Kamicha && player_number != 3 && !hai.is_jihai() {
+        let previous = hai.previous(player_number, false);
+        let previous2 = previous.and_then(|cur| cur.previous(player_number, false));
+        let next = hai.next(player_number, false);
+        let next2 = next.and_then(|cur| cur.next(player_number, false));
+        for (first, second) in [(previous2, previous), (previous, next), (next, next2)] {
+            let (first, second) = match (first, second) {
+                (Some(first), Some(second)) => (first, second),
+                _ => continue,
+            };
+            let second_vec = same_kind(second);
+            for first in same_kind(first).into_iter() {
+                for second in second_vec.iter() {
+                    let naki = Naki::Chii(first, *second);
+                    if !candidates.contains(&naki) {
+                        candidates.push(naki);
+                    }
+                }
+            }
+        }
+    }
+
+    let same = same_kind(hai);
+    for (index, first) in same.iter().enumerate() {
+        for second in &same[index + 1..] {
+            let naki = Naki::Pon(*first, *second);
+            if !candidates.contains(&naki) {
+                candidates.push(naki);
+            }
+        }
+    }
+    if same.len() >= 3 {
+        candidates.push(Naki::Daiminkan);
+    }
+    Ok(candidates)
+}
+
+/// Resolve calls of all players on the same hai, returning the ones taking effect with
+/// seats, in turn order from the discarder.
+///
+/// Ron is prior to pon and daiminkan, which is prior to chii. More than one ron all take
+/// effect unless `rule.atamahane`. Otherwise only one call takes effect, or none if
+/// everyone passed.
+///
+/// # Parameters
+/// * seat: seat of the player making the sutehai, whose answer is ignored.
+/// * answers: calls of every player by seat, `None` to pass.
+/// * rule: rules of resolving.
+pub fn resolve_naki(seat: usize, answers: &[Option<Naki>], rule: &NakiRule) -> Vec<(usize, Naki)> {
+    let player_number = answers.len();
+    let calls: Vec<(usize, Naki)> = (1..player_number)
+        .map(|offset| (seat + offset) % player_number)
+        .filter_map(|caller| answers[caller].map(|naki| (caller, naki)))
+        .collect();
+
+    let ron: Vec<(usize, Naki)> = calls
+        .iter()
+        .filter(|(_, naki)| *naki == Naki::Ron)
+        .copied()
+        .take(if rule.atamahane { 1 } else { player_number })
+        .collect();
+    if !ron.is_empty() {
+        return ron;
+    }
+    calls
+        .iter()
+        .find(|(_, naki)| matches!(naki, Naki::Pon(..) | Naki::Daiminkan))
+        .or_else(|| {
+            calls
+                .iter()
+                .find(|(_, naki)| matches!(naki, Naki::Chii(..)))
+        })
+        .copied()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(tehai: &str, hai: Hai, from: Tacha) -> Vec<Naki> {
+        let tehai = Tehai::new(tehai.to_string()).unwrap();
+        naki_candidates(&tehai, hai, from).unwrap()
+    }
+
+    #[test]
+    fn chii_and_pon() {
+        assert_eq!(
+            candidates("34067m11p139s555z", Hai::Manzu(5), Tacha::Kamicha),
+            [
+                Naki::Chii(Hai::Manzu(3), Hai::Manzu(4)),
+                Naki::Chii(Hai::Manzu(4), Hai::Manzu(6)),
+                Naki::Chii(Hai::Manzu(6), Hai::Manzu(7)),
+            ]
+        );
+        assert_eq!(
+            candidates("34067m11p139s555z", Hai::Manzu(5), Tacha::Toimen),
+            []
+        );
+        assert_eq!(
+            candidates("4556m11p123s5556z", Hai::Manzu(0), Tacha::Kamicha),
+            [
+                Naki::Chii(Hai::Manzu(4), Hai::Manzu(6)),
+                Naki::Pon(Hai::Manzu(5), Hai::Manzu(5)),
+            ]
+        );
+        assert_eq!(
+            candidates("550p11p123s55566z", Hai::Pinzu(5), Tacha::Shimocha),
+            [
+                Naki::Pon(Hai::Pinzu(5), Hai::Pinzu(5)),
+                Naki::Pon(Hai::Pinzu(5), Hai::Pinzu(0)),
+                Naki::Daiminkan,
+            ]
+        );
+        assert_eq!(
+            candidates("1234m[777z][999p]", Hai::Manzu(2), Tacha::Kamicha),
+            [
+                Naki::Chii(Hai::Manzu(1), Hai::Manzu(3)),
+                Naki::Chii(Hai::Manzu(3), Hai::Manzu(4)),
+            ]
+        );
+        assert_eq!(candidates("1234567z", Hai::Jihai(1), Tacha::Kamicha), []);
+    }
+
+    #[test]
+    fn ron() {
+        assert_eq!(
+            candidates("1234m[777z][999p]", Hai::Manzu(1), Tacha::Toimen),
+            [Naki::Ron]
+        );
+        assert_eq!(
+            candidates("1234m[777z][999p]", Hai::Manzu(4), Tacha::Kamicha),
+            [Naki::Ron, Naki::Chii(Hai::Manzu(2), Hai::Manzu(3))]
+        );
+        let tehai = Tehai::new("1234m[777z][999p]1m".to_string()).unwrap();
+        assert!(naki_candidates(&tehai, Hai::Manzu(1), Tacha::Toimen).is_err());
+    }
+
+    #[test]
+    fn resolve() {
+        let chii = Naki::Chii(Hai::Manzu(3), Hai::Manzu(4));
+        let pon = Naki::Pon(Hai::Manzu(5), Hai::Manzu(5));
+        let atamahane = NakiRule::default();
+        let daburon = NakiRule { atamahane: false };
+
+        assert_eq!(resolve_naki(0, &[None; 4], &atamahane), []);
+        assert_eq!(
+            resolve_naki(0, &[None, Some(chii), Some(pon), None], &atamahane),
+            [(2, pon)]
+        );
+        assert_eq!(
+            resolve_naki(0, &[None, Some(chii), None, None], &atamahane),
+            [(1, chii)]
+        );
+        assert_eq!(
+            resolve_naki(
+                2,
+                &[Some(Naki::Ron), Some(Naki::Ron), Some(Naki::Ron), Some(pon)],
+                &atamahane
+            ),
+            [(0, Naki::Ron)]
+        );
+        assert_eq!(
+            resolve_naki(
+                2,
+                &[Some(Naki::Ron), Some(Naki::Ron), None, Some(pon)],
+                &daburon
+            ),
+            [(0, Naki::Ron), (1, Naki::Ron)]
+        );
+        assert_eq!(
+            resolve_naki(1, &[Some(Naki::Ron), None, Some(pon)], &daburon),
+            [(0, Naki::Ron)]
+        );
+    }
+}
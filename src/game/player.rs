@@ -0,0 +1,146 @@
+use crate::calculator::{Error, Hai, Tehai};
+
+/// A hai in kawa.
+///
+/// # Japanese
+/// * Sutehai: 捨て牌
+/// * tsumogiri: 自摸切り
+/// * naki: 鳴き
+///
+/// # Member
+/// * hai: the hai discarded.
+/// * tsumogiri: discarded right after drawn.
+/// * riichi: discarded to declare riichi.
+/// * naki: called by another player, so moved into the fuuro.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Sutehai {
+    pub hai: Hai,
+    pub tsumogiri: bool,
+    pub riichi: bool,
+    pub naki: bool,
+}
+
+/// A player in a kyoku.
+///
+/// # Japanese
+/// * kawa: 河
+/// * riichi: 立直
+/// * double_riichi: 両立直
+/// * ippatsu: 一発
+/// * furiten: 振り聴
+///
+/// # Member
+/// * tehai: the tehai, with the hai just drawn in juntehai.
+/// * kawa: all sutehai in order, called ones included.
+/// * riichi: riichi accepted.
+/// * double_riichi: riichi accepted on the first uninterrupted turn.
+/// * ippatsu: riichi accepted and not yet interrupted by any call or own sutehai.
+/// * furiten: passed a winning hai since the last sutehai, or since riichi. Winning hai in
+///   kawa are checked apart.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Player {
+    pub tehai: Tehai,
+    pub kawa: Vec<Sutehai>,
+    pub riichi: bool,
+    pub double_riichi: bool,
+    pub ippatsu: bool,
+    pub furiten: bool,
+}
+
+impl Player {
+    /// Create a player with a dealt juntehai.
+    pub fn new(juntehai: Vec<Hai>) -> Self {
+        Self {
+            tehai: Tehai {
+                juntehai,
+                fuuro: vec![],
+                nukidora: 0,
+                player_number: 4,
+            },
+            kawa: vec![],
+            riichi: false,
+            double_riichi: false,
+            ippatsu: false,
+            furiten: false,
+        }
+    }
+
+    /// Return all winning hai of a 3*k+1 tehai, empty if not tenpai.
+    ///
+    /// Same as `Tehai::machihai`, but shanten table is used here for every action, so it
+    /// is much faster than decomposing tehai.
+    pub fn machihai(&self) -> Result<Vec<Hai>, Error> {
+        self.tehai.machihai_by(Tehai::shanten)
+    }
+
+    /// Return if a 3*k+1 tehai is tenpai.
+    pub fn is_tenpai(&self) -> Result<bool, Error> {
+        Ok(!self.machihai()?.is_empty())
+    }
+
+    /// Return if any winning hai is in kawa or passed, so the player cannot ron.
+    pub fn is_furiten(&self) -> Result<bool, Error> {
+        if self.furiten {
+            return Ok(true);
+        }
+        let machihai = self.machihai()?;
+        Ok(self
+            .kawa
+            .iter()
+            .any(|sutehai| machihai.contains(&sutehai.hai.normalize())))
+    }
+
+    /// Return if the tehai is menzen, which means no fuuro but ankan.
+    pub fn is_menzen(&self) -> bool {
+        self.tehai.fuuro.iter().all(|fuuro| !fuuro.is_open())
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Create a player with tehai and kawa, neither called nor riichi.
+    pub(in crate::game) fn player(tehai: &str, kawa: &[Hai]) -> Player {
+        Player {
+            tehai: Tehai::new(tehai.to_string()).unwrap(),
+            kawa: kawa.iter().map(|hai| sutehai(*hai)).collect(),
+            ..Player::new(vec![])
+        }
+    }
+
+    /// Create a sutehai neither tsumogiri, riichi nor called.
+    pub(in crate::game) fn sutehai(hai: Hai) -> Sutehai {
+        Sutehai {
+            hai,
+            tsumogiri: false,
+            riichi: false,
+            naki: false,
+        }
+    }
+
+    #[test]
+    fn machihai() {
+        for input in [
+            "123m456p789s1122z",
+            "1112345678999m",
+            "2223444m[777z][<123s]",
+            "123m456p789s1111z",
+            "123m456p789s3z[1111z]",
+            "1199m1199p1155s3z",
+            "19m19p19s1234567z",
+            "135m456p789s1234z",
+        ] {
+            let mut player = player(input, &[]);
+            if player.tehai.juntehai.len() % 3 == 2 {
+                player.tehai.juntehai.pop();
+            }
+            assert_eq!(
+                player.machihai().unwrap(),
+                player.tehai.machihai().unwrap(),
+                "{}",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use super::{Error, Player};
+
+/// Points paid by noten players to tenpai players in total.
+const NOTEN_BAPPU: i32 = 3000;
+
+/// Points of mangan paid by each ko to oya for tsumo.
+const MANGAN_OYA: i32 = 4000;
+
+/// Points of mangan paid by each other ko to ko for tsumo.
+const MANGAN_KO: i32 = 2000;
+
+/// Settlement of ryuukyoku when no hai left to draw.
+///
+/// # Japanese
+/// * Ryuukyoku: 流局
+/// * tenpai: 聴牌
+/// * nagashi_mangan: 流し満貫
+/// * noten bappu: 不聴罰符
+///
+/// # Member
+/// * tenpai: if each player is tenpai, by seat.
+/// * nagashi_mangan: if each player made nagashi mangan, by seat, which means kawa of only
+///   yaochuupai and none of them called.
+/// * delta: points change of each player, by seat.
+///
+/// Noten players pay 3000 points in total to tenpai players, unless everyone or no one is
+/// tenpai. When anyone made nagashi mangan, it is paid as mangan by tsumo instead, and
+/// tenpai players get nothing. Honba and kyoutaku are not paid either way.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ryuukyoku {
+    pub tenpai: Vec<bool>,
+    pub nagashi_mangan: Vec<bool>,
+    pub delta: Vec<i32>,
+}
+
+impl Ryuukyoku {
+    /// Settle ryuukyoku of players, each with 3*k+1 tehai.
+    ///
+    /// # Parameters
+    /// * players: all players by seat.
+    /// * oya: seat of oya.
+    pub fn new(players: &[Player], oya: usize) -> Result<Self, Error> {
+        let player_number = players.len();
+        // Decomposing is slow but sure, only once at the end of kyoku.
+        let mut tenpai = vec![];
+        for player in players {
+            tenpai.push(player.tehai.is_tenpai()?);
+        }
+        let nagashi_mangan: Vec<bool> = players.iter().map(is_nagashi_mangan).collect();
+
+        let mut delta = vec![0; player_number];
+        if nagashi_mangan.contains(&true) {
+            for seat in (0..player_number).filter(|seat| nagashi_mangan[*seat]) {
+                for other in (0..player_number).filter(|other| *other != seat) {
+                    let payment = if seat == oya || other == oya {
+                        MANGAN_OYA
+                    } else {
+                        MANGAN_KO
+                    };
+                    delta[other] -= payment;
+                    delta[seat] += payment;
+                }
+            }
+        } else {
+            let tenpai_number = tenpai.iter().filter(|tenpai| **tenpai).count();
+            if tenpai_number > 0 && tenpai_number < player_number {
+                for (seat, tenpai) in tenpai.iter().enumerate() {
+                    delta[seat] = if *tenpai {
+                        NOTEN_BAPPU / tenpai_number as i32
+                    } else {
+                        -NOTEN_BAPPU / (player_number - tenpai_number) as i32
+                    };
+                }
+            }
+        }
+
+        Ok(Self {
+            tenpai,
+            nagashi_mangan,
+            delta,
+        })
+    }
+
+    /// Return if oya keeps the seat, by tenpai or nagashi mangan of oya.
+    pub fn is_renchan(&self, oya: usize) -> bool {
+        self.tenpai[oya] || self.nagashi_mangan[oya]
+    }
+}
+
+/// Return if kawa of the player has only yaochuupai, none of them called.
+fn is_nagashi_mangan(player: &Player) -> bool {
+    !player.kawa.is_empty()
+        && player
+            .kawa
+            .iter()
+            .all(|sutehai| sutehai.hai.is_yaochuupai() && !sutehai.naki)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::Hai;
+    use crate::game::player::tests::player;
+
+    #[test]
+    fn tenpai() {
+        let tenpai = "123m456p789s1122z";
+        let noten = "135m456p789s1234z";
+        let kawa = [Hai::Manzu(2), Hai::Jihai(1)];
+
+        let players = vec![
+            player(tenpai, &kawa),
+            player(noten, &kawa),
+            player(noten, &kawa),
+            player(noten, &kawa),
+        ];
+        let ryuukyoku = Ryuukyoku::new(&players, 0).unwrap();
+        assert_eq!(ryuukyoku.tenpai, [true, false, false, false]);
+        assert_eq!(ryuukyoku.nagashi_mangan, [false; 4]);
+        assert_eq!(ryuukyoku.delta, [3000, -1000, -1000, -1000]);
+        assert!(ryuukyoku.is_renchan(0));
+        assert!(!ryuukyoku.is_renchan(1));
+
+        let players = vec![
+            player(tenpai, &kawa),
+            player(noten, &kawa),
+            player(tenpai, &kawa),
+            player(noten, &kawa),
+        ];
+        let ryuukyoku = Ryuukyoku::new(&players, 0).unwrap();
+        assert_eq!(ryuukyoku.delta, [1500, -1500, 1500, -1500]);
+
+        let players = vec![player(noten, &kawa); 4];
+        let ryuukyoku = Ryuukyoku::new(&players, 0).unwrap();
+        assert_eq!(ryuukyoku.delta, [0; 4]);
+
+        // Waiting only for the fifth 1z is not tenpai.
+        let ryuukyoku = Ryuukyoku::new(&[player("123m456p789s1111z", &kawa)], 0).unwrap();
+        assert_eq!(ryuukyoku.tenpai, [false]);
+        let ryuukyoku = Ryuukyoku::new(&[player("123m456p789s3z[1111z]", &kawa)], 0).unwrap();
+        assert_eq!(ryuukyoku.tenpai, [true]);
+    }
+
+    #[test]
+    fn nagashi_mangan() {
+        let tenpai = "123m456p789s1122z";
+        let yaochuupai = [Hai::Manzu(1), Hai::Jihai(5), Hai::Souzu(9)];
+        let kawa = [Hai::Manzu(2), Hai::Jihai(1)];
+
+        let mut players = vec![
+            player(tenpai, &kawa),
+            player(tenpai, &yaochuupai),
+            player(tenpai, &kawa),
+            player(tenpai, &kawa),
+        ];
+        let ryuukyoku = Ryuukyoku::new(&players, 0).unwrap();
+        assert_eq!(ryuukyoku.nagashi_mangan, [false, true, false, false]);
+        assert_eq!(ryuukyoku.delta, [-4000, 8000, -2000, -2000]);
+        assert!(ryuukyoku.is_renchan(0));
+
+        players[0].kawa = players[1].kawa.clone();
+        let ryuukyoku = Ryuukyoku::new(&players, 0).unwrap();
+        assert_eq!(ryuukyoku.delta, [8000, 4000, -6000, -6000]);
+
+        players[1].kawa[1].naki = true;
+        let ryuukyoku = Ryuukyoku::new(&players, 0).unwrap();
+        assert_eq!(ryuukyoku.nagashi_mangan, [true, false, false, false]);
+        assert_eq!(ryuukyoku.delta, [12000, -4000, -4000, -4000]);
+    }
+}
@@ -0,0 +1,241 @@
+use super::{Naki, Player};
+use crate::calculator::{Hai, Mentsu};
+
+/// Abortive draw, ending a kyoku before yama runs out.
+///
+/// # Japanese
+/// * TochuuRyuukyoku: 途中流局
+/// * KyuushuKyuuhai: 九種九牌
+/// * SuufonRenda: 四風連打
+/// * SuuchaRiichi: 四家立直
+/// * Suukaikan: 四開槓
+/// * Sanchahou: 三家和
+///
+/// # Variant
+/// * KyuushuKyuuhai: a player declared it with 9 or more kinds of yaochuupai on the first
+///   uninterrupted draw.
+/// * SuufonRenda: all 4 players discarded the same kazehai on the first uninterrupted turn.
+/// * SuuchaRiichi: all 4 players riichi.
+/// * Suukaikan: 4 kan made by more than one player.
+/// * Sanchahou: 3 players ron on the same hai.
+///
+/// Honba increases after any of them and oya keeps the seat, as by most rules, but it is
+/// left to the caller.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TochuuRyuukyoku {
+    KyuushuKyuuhai,
+    SuufonRenda,
+    SuuchaRiichi,
+    Suukaikan,
+    Sanchahou,
+}
+
+/// Which abortive draws are used, all of them by default.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TochuuRule {
+    pub kyuushu_kyuuhai: bool,
+    pub suufon_renda: bool,
+    pub suucha_riichi: bool,
+    pub suukaikan: bool,
+    pub sanchahou: bool,
+}
+
+impl Default for TochuuRule {
+    fn default() -> Self {
+        Self {
+            kyuushu_kyuuhai: true,
+            suufon_renda: true,
+            suucha_riichi: true,
+            suukaikan: true,
+            sanchahou: true,
+        }
+    }
+}
+
+impl TochuuRule {
+    /// Return if the player of `seat` may declare kyuushu kyuuhai, on the first draw without
+    /// any call or kan before.
+    ///
+    /// # Parameters
+    /// * seat: seat of the player just drawn.
+    /// * players: all players by seat.
+    pub fn can_kyuushu_kyuuhai(&self, seat: usize, players: &[Player]) -> bool {
+        let player = &players[seat];
+        self.kyuushu_kyuuhai
+            && player.kawa.is_empty()
+            && players.iter().all(|player| player.tehai.fuuro.is_empty())
+            && yaochuupai_kind_number(&player.tehai.juntehai) >= 9
+    }
+
+    /// Check abortive draws after a sutehai passed without ron, returning the one applies.
+    ///
+    /// Suufon renda is checked first, then suukaikan and suucha riichi. The riichi of the
+    /// sutehai should have been accepted.
+    ///
+    /// # Parameters
+    /// * players: all players by seat.
+    pub fn check_dahai(&self, players: &[Player]) -> Option<TochuuRyuukyoku> {
+        if self.suufon_renda && is_suufon_renda(players) {
+            return Some(TochuuRyuukyoku::SuufonRenda);
+        }
+        if self.suukaikan && is_suukaikan(players) {
+            return Some(TochuuRyuukyoku::Suukaikan);
+        }
+        if self.suucha_riichi && players.len() == 4 && players.iter().all(|player| player.riichi) {
+            return Some(TochuuRyuukyoku::SuuchaRiichi);
+        }
+        None
+    }
+
+    /// Check sanchahou in answers to the sutehai of `seat`, returning it if applies.
+    ///
+    /// # Parameters
+    /// * seat: seat of the player making the sutehai, whose answer is ignored.
+    /// * answers: calls of every player by seat, `None` to pass.
+    pub fn check_answers(&self, seat: usize, answers: &[Option<Naki>]) -> Option<TochuuRyuukyoku> {
+        let ron_number = answers
+            .iter()
+            .enumerate()
+            .filter(|(caller, naki)| *caller != seat && **naki == Some(Naki::Ron))
+            .count();
+        if self.sanchahou && ron_number >= 3 {
+            Some(TochuuRyuukyoku::Sanchahou)
+        } else {
+            None
+        }
+    }
+}
+
+/// Return the number of kinds of yaochuupai in juntehai.
+fn yaochuupai_kind_number(juntehai: &[Hai]) -> usize {
+    Hai::yaochuupai_type()
+        .into_iter()
+        .filter(|hai| juntehai.contains(hai))
+        .count()
+}
+
+fn is_suufon_renda(players: &[Player]) -> bool {
+    let first = match players.first().and_then(|player| player.kawa.first()) {
+        Some(sutehai) => sutehai.hai,
+        None => return false,
+    };
+    players.len() == 4
+        && first.is_kazehai()
+        && players.iter().all(|player| {
+            player.tehai.fuuro.is_empty() && player.kawa.len() == 1 && player.kawa[0].hai == first
+        })
+}
+
+fn is_suukaikan(players: &[Player]) -> bool {
+    let kan_numbers: Vec<usize> = players
+        .iter()
+        .map(|player| {
+            player
+                .tehai
+                .fuuro
+                .iter()
+                .filter(|fuuro| matches!(fuuro.mentsu, Mentsu::Kantsu(_)))
+                .count()
+        })
+        .collect();
+    kan_numbers.iter().sum::<usize>() >= 4
+        && kan_numbers.iter().filter(|number| **number > 0).count() > 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::Tehai;
+    use crate::game::player::tests::{player, sutehai};
+
+    #[test]
+    fn kyuushu_kyuuhai() {
+        let rule = TochuuRule::default();
+        let mut players = vec![
+            player("19m19p1s1234z2345m", &[]),
+            player("1234m5678p12345s", &[]),
+            player("1234m5678p12345s", &[]),
+            player("1234m5678p12345s", &[]),
+        ];
+        assert!(rule.can_kyuushu_kyuuhai(0, &players));
+        assert!(!rule.can_kyuushu_kyuuhai(1, &players));
+        let off = TochuuRule {
+            kyuushu_kyuuhai: false,
+            ..rule
+        };
+        assert!(!off.can_kyuushu_kyuuhai(0, &players));
+
+        players[0].tehai = Tehai::new("19m19p1s1233z23455m".to_string()).unwrap();
+        assert!(!rule.can_kyuushu_kyuuhai(0, &players));
+
+        players[0].tehai = Tehai::new("19m19p1s1234z2345m".to_string()).unwrap();
+        players[2].tehai = Tehai::new("1234m5678p12s[345s]".to_string()).unwrap();
+        assert!(!rule.can_kyuushu_kyuuhai(0, &players));
+    }
+
+    #[test]
+    fn suufon_renda() {
+        let rule = TochuuRule::default();
+        let mut players = vec![player("1234m5678p12345s", &[]); 4];
+        assert_eq!(rule.check_dahai(&players), None);
+        for player in players.iter_mut() {
+            player.kawa.push(sutehai(Hai::Jihai(2)));
+        }
+        assert_eq!(
+            rule.check_dahai(&players),
+            Some(TochuuRyuukyoku::SuufonRenda)
+        );
+
+        players[3].kawa[0] = sutehai(Hai::Jihai(5));
+        assert_eq!(rule.check_dahai(&players), None);
+    }
+
+    #[test]
+    fn suucha_riichi_and_suukaikan() {
+        let rule = TochuuRule::default();
+        let mut players = vec![player("1234m5678p12345s", &[]); 4];
+        for player in players.iter_mut() {
+            player.riichi = true;
+        }
+        assert_eq!(
+            rule.check_dahai(&players),
+            Some(TochuuRyuukyoku::SuuchaRiichi)
+        );
+        let off = TochuuRule {
+            suucha_riichi: false,
+            ..rule
+        };
+        assert_eq!(off.check_dahai(&players), None);
+
+        // Suukantsu may still be made by one player.
+        let mut players = vec![player("1234m5678p12345s", &[]); 4];
+        players[0].tehai = Tehai::new("1m(1111z)(2222z)(3333z)(4444z)".to_string()).unwrap();
+        assert_eq!(rule.check_dahai(&players), None);
+
+        players[0].tehai = Tehai::new("1m(1111z)(2222z)(3333z)".to_string()).unwrap();
+        assert_eq!(rule.check_dahai(&players), None);
+        players[1].tehai = Tehai::new("234m5678p123s(4444z)".to_string()).unwrap();
+        assert_eq!(rule.check_dahai(&players), Some(TochuuRyuukyoku::Suukaikan));
+        let off = TochuuRule {
+            suukaikan: false,
+            ..rule
+        };
+        assert_eq!(off.check_dahai(&players), None);
+    }
+
+    #[test]
+    fn sanchahou() {
+        let rule = TochuuRule::default();
+        let ron = Some(Naki::Ron);
+        assert_eq!(
+            rule.check_answers(0, &[None, ron, ron, ron]),
+            Some(TochuuRyuukyoku::Sanchahou)
+        );
+        assert_eq!(rule.check_answers(1, &[None, ron, ron, ron]), None);
+        let off = TochuuRule {
+            sanchahou: false,
+            ..rule
+        };
+        assert_eq!(off.check_answers(0, &[None, ron, ron, ron]), None);
+    }
+}
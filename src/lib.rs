@@ -0,0 +1,19 @@
+//! Riichi mahjong calculator for shanten, machihai, ukeire and points.
+//!
+//! * `calculator`: parsing and analyzing tehai, usable as a library.
+//! * `game`: a 4 player hanchan driven by actions, emitting events.
+//! * `controller`: text and JSON front end used by the binary.
+//!
+//! ```
+//! use r_mahjong::calculator::{Taku, Tehai};
+//!
+//! let tehai = Tehai::new("123m456p789s11z22z".to_string()).unwrap();
+//! let ukeire = tehai.ukeire(&Taku::default()).unwrap();
+//! assert_eq!(ukeire.shanten, 0);
+//! assert_eq!(ukeire.nokori(), 4);
+//! ```
+#![forbid(unsafe_code)]
+
+pub mod calculator;
+pub mod controller;
+pub mod game;
@@ -1,16 +1,78 @@
 #![forbid(unsafe_code)]
 
-mod calculator;
-mod controller;
+use r_mahjong::controller::{Controller, Repl};
 use std::env;
+use std::fs::File;
+use std::io::{stdin, stdout, BufReader};
+use std::process;
+
+const USAGE: &str = "\
+Usage: r-mahjong [options] [tehai]
+
+Analyze a tehai like 1122345m4689s357p, or start an interactive session without tehai.
+
+Options:
+  --sanma           3-players mode
+  --dora=<hai>      dora indicators, like --dora=1z3p
+  --ura=<hai>       uradora indicators
+  --kawa=<hai>      sutehai of the player, deciding furiten
+  --tacha=<hai>     hai visible from other players
+  --kairyou         analyze kairyouhai, which takes much more time
+  --agari=<hai>     hai completing the tehai, or the one with most points if not given
+  --tsumo           win by tsumo, otherwise by ron
+  --riichi          riichi declared, counting uradora
+  --honba=<n>       number of honba sticks
+  --kyoutaku=<n>    number of riichi sticks on the table
+  --bakaze=<hai>    wind of the round, 1z by default
+  --jikaze=<hai>    wind of the player, 2z by default
+  --unicode         print hai as mahjong tiles
+  --color           print hai in colors
+  --json            print in JSON
+  --batch[=<path>]  analyze each line of the file, or stdin without path
+  -h, --help        show this message";
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let (mut controller, command) = match Controller::from_args(&args) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    };
 
-    let args: Vec<String> = env::args().collect();
+    // `--batch` reads tehai from stdin, and `--batch=<path>` from the file.
+    let batch = args.iter().find_map(|arg| match arg.as_str() {
+        "--batch" => Some("-"),
+        arg => arg.strip_prefix("--batch="),
+    });
+    if let Some(path) = batch {
+        let result = if path == "-" {
+            controller.batch(stdin().lock(), &mut stdout())
+        } else {
+            File::open(path).and_then(|file| controller.batch(BufReader::new(file), &mut stdout()))
+        };
+        if let Err(error) = result {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+        return;
+    }
 
-    let mut controller = controller::Controller::new(controller::OutputFormat::Standard);
-    let res = controller.execute(args[1].clone());
+    if command.is_empty() {
+        if let Err(error) = Repl::new(controller).run(stdin().lock(), &mut stdout()) {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+        return;
+    }
+    let res = controller.execute(command);
 
     println!("{}", res);
-    
-}
\ No newline at end of file
+}